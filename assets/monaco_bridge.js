@@ -0,0 +1,881 @@
+// Dispatcher for the Rust <-> Monaco bridge, see src/monaco/protocol.rs.
+//
+// Every MonacoEditor instance evaluates this file with `PROTOCOL_VERSION` bound,
+// then hands its eval channel to `monacoBridge.run`. The dispatcher itself is only
+// defined once per page.
+if (!window.monacoBridge || window.monacoBridge.version !== PROTOCOL_VERSION) {
+    window.monacoBridge = (function () {
+        const VERSION = PROTOCOL_VERSION;
+        const instances = {};
+        let loading = null;
+
+        // Monaco is loaded once per page; every instance waits on the same promise
+        function loadMonaco() {
+            if (typeof monaco !== 'undefined' && monaco.editor) {
+                return Promise.resolve();
+            }
+            if (!loading) {
+                loading = new Promise((resolve, reject) => {
+                    const loadMain = () => require(['vs/editor/editor.main'], () => {
+                        console.log('Monaco main loaded');
+                        resolve();
+                    }, (err) => reject(err || new Error('Failed to load vs/editor/editor.main')));
+
+                    if (typeof require !== 'undefined' && require.config) {
+                        // The loader from the custom head is already configured for the local copy
+                        loadMain();
+                        return;
+                    }
+
+                    console.log('Loading Monaco from CDN...');
+                    const cdnScript = document.createElement('script');
+                    cdnScript.src = 'https://cdn.jsdelivr.net/npm/monaco-editor@0.44.0/min/vs/loader.js';
+                    cdnScript.onload = function () {
+                        require.config({
+                            paths: { 'vs': 'https://cdn.jsdelivr.net/npm/monaco-editor@0.44.0/min/vs' }
+                        });
+                        loadMain();
+                    };
+                    cdnScript.onerror = function () {
+                        reject(new Error('Failed to load Monaco from CDN'));
+                    };
+                    document.head.appendChild(cdnScript);
+                });
+            }
+            return loading;
+        }
+
+        function errorFields(error) {
+            return {
+                message: String(error && error.message ? error.message : error),
+                stack: error && error.stack ? String(error.stack) : null
+            };
+        }
+
+        // Results go back as JSON; this drops methods and fails loudly on cycles
+        function plain(value) {
+            return value === undefined ? null : JSON.parse(JSON.stringify(value));
+        }
+
+        // Monaco providers are global per language, so each kind is registered once per
+        // language and asks whichever instance owns the model in question. The spec it was
+        // registered with is kept, as trigger characters and the like are fixed at registration.
+        const registered = {};
+
+        function ownerOf(model) {
+            for (const key of Object.keys(instances)) {
+                const instance = instances[key];
+                if (!instance.disposed && instance.editor && instance.editor.getModel() === model) {
+                    return instance;
+                }
+            }
+            return null;
+        }
+
+        // Resolves to Rust's answer, or null if nobody on the Rust side serves `kind` for `model`
+        function forward(kind, model, query, token) {
+            const instance = ownerOf(model);
+            if (!instance || !instance.providers[kind]) {
+                return Promise.resolve(null);
+            }
+            return instance.provide(Object.assign({ kind }, query), token);
+        }
+
+        const adapters = {
+            completion(language, spec) {
+                return monaco.languages.registerCompletionItemProvider(language, {
+                    triggerCharacters: spec.trigger_characters,
+                    async provideCompletionItems(model, position, context, token) {
+                        const word = model.getWordUntilPosition(position);
+                        const items = await forward('completion', model, {
+                            position: { lineNumber: position.lineNumber, column: position.column },
+                            word: word.word,
+                            trigger_character: context.triggerCharacter || null
+                        }, token);
+                        const range = {
+                            startLineNumber: position.lineNumber,
+                            startColumn: word.startColumn,
+                            endLineNumber: position.lineNumber,
+                            endColumn: word.endColumn
+                        };
+                        return {
+                            suggestions: (items || []).map((item) => ({
+                                label: item.label,
+                                kind: monaco.languages.CompletionItemKind[item.kind],
+                                insertText: item.insertText,
+                                insertTextRules: item.isSnippet
+                                    ? monaco.languages.CompletionItemInsertTextRule.InsertAsSnippet
+                                    : undefined,
+                                detail: item.detail,
+                                documentation: item.documentation ? { value: item.documentation } : undefined,
+                                range: item.range || range
+                            }))
+                        };
+                    }
+                });
+            },
+
+            hover(language) {
+                return monaco.languages.registerHoverProvider(language, {
+                    async provideHover(model, position, token) {
+                        const word = model.getWordAtPosition(position);
+                        const hover = await forward('hover', model, {
+                            position: { lineNumber: position.lineNumber, column: position.column },
+                            word: word ? word.word : null
+                        }, token);
+                        if (!hover) {
+                            return null;
+                        }
+                        const range = word ? {
+                            startLineNumber: position.lineNumber,
+                            startColumn: word.startColumn,
+                            endLineNumber: position.lineNumber,
+                            endColumn: word.endColumn
+                        } : undefined;
+                        return {
+                            contents: hover.contents.map((value) => ({ value })),
+                            range: hover.range || range
+                        };
+                    }
+                });
+            },
+
+            signature_help(language, spec) {
+                const markdown = (value) => (value ? { value } : undefined);
+                return monaco.languages.registerSignatureHelpProvider(language, {
+                    signatureHelpTriggerCharacters: spec.trigger_characters,
+                    signatureHelpRetriggerCharacters: spec.retrigger_characters,
+                    async provideSignatureHelp(model, position, token, context) {
+                        const help = await forward('signature_help', model, {
+                            position: { lineNumber: position.lineNumber, column: position.column },
+                            trigger_character: context.triggerCharacter || null,
+                            is_retrigger: !!context.isRetrigger
+                        }, token);
+                        if (!help) {
+                            return null;
+                        }
+                        return {
+                            value: {
+                                signatures: help.signatures.map((signature) => ({
+                                    label: signature.label,
+                                    documentation: markdown(signature.documentation),
+                                    parameters: signature.parameters.map((parameter) => ({
+                                        label: parameter.label,
+                                        documentation: markdown(parameter.documentation)
+                                    }))
+                                })),
+                                activeSignature: help.activeSignature,
+                                activeParameter: help.activeParameter
+                            },
+                            dispose() {}
+                        };
+                    }
+                });
+            },
+
+            code_action(language, spec) {
+                return monaco.languages.registerCodeActionProvider(language, {
+                    async provideCodeActions(model, range, context, token) {
+                        const actions = await forward('code_action', model, {
+                            range: plainRange(range),
+                            markers: context.markers.map((marker) => Object.assign(plainRange(marker), {
+                                severity: marker.severity,
+                                message: marker.message,
+                                code: marker.code && typeof marker.code === 'object'
+                                    ? marker.code.value : (marker.code || null)
+                            })),
+                            only: context.only || null
+                        }, token);
+                        return {
+                            actions: (actions || []).map((action) => ({
+                                title: action.title,
+                                kind: action.kind,
+                                diagnostics: action.diagnostics,
+                                edit: workspaceEdit(action.edit, model),
+                                isPreferred: action.isPreferred
+                            })),
+                            dispose() {}
+                        };
+                    }
+                }, { providedCodeActionKinds: spec.kinds });
+            },
+
+            code_lens(language) {
+                return monaco.languages.registerCodeLensProvider(language, {
+                    async provideCodeLenses(model, token) {
+                        const instance = ownerOf(model);
+                        const lenses = await forward('code_lens', model, {}, token);
+                        if (!lenses || !instance) {
+                            return null;
+                        }
+                        return {
+                            // Every lens runs the instance's command, which hands the real id to Rust
+                            lenses: lenses.map((lens) => ({
+                                range: lens.range,
+                                command: {
+                                    id: instance.commandId,
+                                    title: lens.command.title,
+                                    tooltip: lens.command.tooltip,
+                                    arguments: [lens.command.id].concat(lens.command.arguments)
+                                }
+                            })),
+                            dispose() {}
+                        };
+                    }
+                });
+            },
+
+            document_symbol(language) {
+                const toMonaco = (symbol) => ({
+                    name: symbol.name,
+                    detail: symbol.detail,
+                    kind: monaco.languages.SymbolKind[symbol.kind],
+                    tags: [],
+                    range: symbol.range,
+                    selectionRange: symbol.selectionRange,
+                    children: symbol.children.map(toMonaco)
+                });
+                return monaco.languages.registerDocumentSymbolProvider(language, {
+                    async provideDocumentSymbols(model, token) {
+                        const symbols = await forward('document_symbol', model, {}, token);
+                        return symbols ? symbols.map(toMonaco) : null;
+                    }
+                });
+            },
+
+            rename(language) {
+                return monaco.languages.registerRenameProvider(language, {
+                    async provideRenameEdits(model, position, newName, token) {
+                        const query = Object.assign(positionQuery(model, position), { new_name: newName });
+                        const result = await forward('rename', model, query, token);
+                        if (!result) {
+                            return null;
+                        }
+                        if (result.reject_reason) {
+                            return { edits: [], rejectReason: result.reject_reason };
+                        }
+                        return workspaceEdit(result.edit, model);
+                    }
+                });
+            },
+
+            definition(language) {
+                registerOpener();
+                return monaco.languages.registerDefinitionProvider(language, {
+                    async provideDefinition(model, position, token) {
+                        const locations = await forward('definition', model, positionQuery(model, position), token);
+                        return (locations || []).map((location) => ({
+                            uri: monaco.Uri.parse(location.uri),
+                            range: location.range
+                        }));
+                    }
+                });
+            },
+
+            // The legend is fixed once the language has a provider
+            semantic_tokens(language, spec) {
+                return monaco.languages.registerDocumentSemanticTokensProvider(language, {
+                    getLegend() {
+                        return spec.legend;
+                    },
+                    async provideDocumentSemanticTokens(model, lastResultId, token) {
+                        const tokens = await forward('semantic_tokens', model, {}, token);
+                        return tokens ? { data: new Uint32Array(tokens.data) } : null;
+                    },
+                    releaseDocumentSemanticTokens() {}
+                });
+            },
+
+            inlay_hint(language) {
+                return monaco.languages.registerInlayHintsProvider(language, {
+                    async provideInlayHints(model, range, token) {
+                        const hints = await forward('inlay_hint', model, { range: plainRange(range) }, token);
+                        return {
+                            hints: (hints || []).map((hint) => ({
+                                position: hint.position,
+                                label: hint.label,
+                                kind: hint.kind ? monaco.languages.InlayHintKind[hint.kind] : undefined,
+                                tooltip: hint.tooltip,
+                                paddingLeft: hint.paddingLeft,
+                                paddingRight: hint.paddingRight
+                            })),
+                            dispose() {}
+                        };
+                    }
+                });
+            },
+
+            inline_completion(language) {
+                return monaco.languages.registerInlineCompletionsProvider(language, {
+                    async provideInlineCompletions(model, position, context, token) {
+                        const items = await forward('inline_completion', model, {
+                            position: { lineNumber: position.lineNumber, column: position.column },
+                            explicit: context.triggerKind === monaco.languages.InlineCompletionTriggerKind.Explicit
+                        }, token);
+                        const cursor = {
+                            startLineNumber: position.lineNumber,
+                            startColumn: position.column,
+                            endLineNumber: position.lineNumber,
+                            endColumn: position.column
+                        };
+                        return {
+                            items: (items || []).map((item) => ({
+                                insertText: item.insertText,
+                                range: item.range || cursor
+                            }))
+                        };
+                    },
+                    freeInlineCompletions() {}
+                });
+            }
+        };
+
+        function plainRange(range) {
+            return {
+                startLineNumber: range.startLineNumber,
+                startColumn: range.startColumn,
+                endLineNumber: range.endLineNumber,
+                endColumn: range.endColumn
+            };
+        }
+
+        // Rust edits name their model by URI, or leave it out for the model asked about
+        function workspaceEdit(edit, model) {
+            return {
+                edits: edit.edits.map((textEdit) => ({
+                    resource: textEdit.uri ? monaco.Uri.parse(textEdit.uri) : model.uri,
+                    textEdit: { range: textEdit.range, text: textEdit.text },
+                    versionId: undefined
+                }))
+            };
+        }
+
+        // Cross-model requests see every model on the page
+        function openDocuments() {
+            return monaco.editor.getModels().map((model) => ({
+                uri: model.uri.toString(),
+                language: model.getLanguageId(),
+                value: model.getValue()
+            }));
+        }
+
+        function positionQuery(model, position) {
+            const word = model.getWordAtPosition(position);
+            return {
+                uri: model.uri.toString(),
+                position: { lineNumber: position.lineNumber, column: position.column },
+                word: word ? word.word : null,
+                documents: openDocuments()
+            };
+        }
+
+        // Definitions in another model are shown by revealing them in the editor that has it
+        let openerRegistered = false;
+
+        function registerOpener() {
+            if (openerRegistered || !monaco.editor.registerEditorOpener) {
+                return;
+            }
+            openerRegistered = true;
+            monaco.editor.registerEditorOpener({
+                openCodeEditor(source, resource, selectionOrPosition) {
+                    for (const key of Object.keys(instances)) {
+                        const editor = instances[key].editor;
+                        if (!instances[key].disposed && editor && editor !== source
+                            && editor.getModel().uri.toString() === resource.toString()) {
+                            if (selectionOrPosition && 'startLineNumber' in selectionOrPosition) {
+                                editor.setSelection(selectionOrPosition);
+                                editor.revealRangeInCenter(selectionOrPosition);
+                            } else if (selectionOrPosition) {
+                                editor.setPosition(selectionOrPosition);
+                                editor.revealPositionInCenter(selectionOrPosition);
+                            }
+                            editor.focus();
+                            return true;
+                        }
+                    }
+                    return false;
+                }
+            });
+        }
+
+        // Tokenizer and configuration registrations of languages defined in Rust, by id
+        const customLanguages = {};
+
+        function registerLanguage(definition) {
+            const id = definition.id;
+            const previous = customLanguages[id];
+            if (previous) {
+                previous.forEach((registration) => registration.dispose());
+            } else {
+                monaco.languages.register({ id, extensions: definition.extensions, aliases: definition.aliases });
+            }
+            const configuration = Object.assign({}, definition.configuration);
+            if (configuration.wordPattern) {
+                configuration.wordPattern = new RegExp(configuration.wordPattern);
+            }
+            customLanguages[id] = [
+                monaco.languages.setMonarchTokensProvider(id, definition.tokenizer),
+                monaco.languages.setLanguageConfiguration(id, configuration)
+            ];
+        }
+
+        function ensureRegistered(language, spec) {
+            const key = spec.kind + ':' + language;
+            const json = JSON.stringify(spec);
+            const previous = registered[key];
+            if (previous && previous.spec === json) {
+                return;
+            }
+            if (previous) {
+                previous.registration.dispose();
+            }
+            registered[key] = { spec: json, registration: adapters[spec.kind](language, spec) };
+        }
+
+        // Shading for protected ranges, added to the page once
+        function ensureProtectedStyle() {
+            if (document.getElementById('monaco-bridge-protected')) {
+                return;
+            }
+            const style = document.createElement('style');
+            style.id = 'monaco-bridge-protected';
+            style.textContent = '.monaco-protected-range { background-color: rgba(128, 128, 128, 0.18); }\n' +
+                '.monaco-protected-lines { border-left: 3px solid rgba(128, 128, 128, 0.7); margin-left: 3px; }';
+            document.head.appendChild(style);
+        }
+
+        class Instance {
+            constructor(key, channel) {
+                this.key = key;
+                this.channel = channel;
+                this.editor = null;
+                // Only set for diff instances, whose `editor` is the modified side
+                this.diffEditor = null;
+                this.disposed = false;
+                // Set while a value from Rust is applied, so it is not echoed back
+                this.applyingRemote = false;
+                // Set while a call from Rust runs; Rust may edit protected text, the user may not
+                this.editingFromRust = false;
+                // Requests run one after the other, in the order Rust sent them
+                this.queue = Promise.resolve();
+                // Provider kinds this instance answers, and the questions still waiting on Rust
+                this.providers = {};
+                this.nextRequest = 0;
+                this.pending = {};
+                // Models created by open_model, by URI; the editor does not dispose them itself
+                this.models = {};
+                // Ranges Rust protected, their decorations on the model they were set on, and
+                // where they were and what the model held before the change being looked at
+                this.protectedRanges = [];
+                this.protectedModel = null;
+                this.protectedIds = [];
+                this.protectedBefore = [];
+                this.protectedValue = '';
+            }
+
+            emit(event) {
+                this.channel.send({ v: VERSION, type: 'event', event });
+            }
+
+            respond(id, outcome) {
+                this.channel.send({ v: VERSION, type: 'response', id, outcome });
+            }
+
+            target(name) {
+                switch (name) {
+                    case 'editor': return this.editor;
+                    case 'model': return this.editor.getModel();
+                    case 'monaco_editor': return monaco.editor;
+                    case 'languages': return monaco.languages;
+                    case 'diff_editor': return this.diffTarget();
+                    case 'original_model': return this.diffTarget().getOriginalEditor().getModel();
+                }
+                throw new Error('Unknown call target: ' + name);
+            }
+
+            diffTarget() {
+                if (!this.diffEditor) {
+                    throw new Error('Not a diff editor: ' + this.key);
+                }
+                return this.diffEditor;
+            }
+
+            // Replaces `{"$ref": name}` objects anywhere inside `value`
+            resolve(value) {
+                if (Array.isArray(value)) {
+                    return value.map((item) => this.resolve(item));
+                }
+                if (value && typeof value === 'object') {
+                    const keys = Object.keys(value);
+                    if (keys.length === 1 && keys[0] === '$ref') {
+                        switch (value.$ref) {
+                            case 'editor': return this.editor;
+                            case 'model': return this.editor.getModel();
+                            case 'original_model': return this.diffTarget().getOriginalEditor().getModel();
+                            case 'selection': return this.editor.getSelection();
+                            case 'position': return this.editor.getPosition();
+                        }
+                        throw new Error('Unknown object ref: ' + value.$ref);
+                    }
+                    const resolved = {};
+                    for (const key of keys) {
+                        resolved[key] = this.resolve(value[key]);
+                    }
+                    return resolved;
+                }
+                return value;
+            }
+
+            async create(request) {
+                await loadMonaco();
+                const container = document.getElementById(this.key);
+                if (!container) {
+                    throw new Error('Container not found');
+                }
+                const editor = monaco.editor.create(container, Object.assign({}, request.options, {
+                    value: request.value
+                }));
+                this.editor = editor;
+
+                // Commands executed in this editor, e.g. from code lenses, are run in Rust
+                this.commandId = editor.addCommand(0, (accessor, id, ...args) => {
+                    this.emit({ event: 'command', id, args: plain(args) });
+                });
+
+                // Push every change to Rust, unless it had to be taken back
+                editor.onDidChangeModelContent((event) => {
+                    if (!this.applyingRemote && !this.rejectProtectedEdit(event)) {
+                        this.emit({ event: 'change', value: editor.getValue() });
+                    }
+                });
+
+                // Layout after a brief delay
+                setTimeout(() => {
+                    if (this.editor === editor) {
+                        editor.layout();
+                    }
+                }, 100);
+
+                console.log('Monaco Editor created successfully!', this.key);
+                this.emit({ event: 'ready' });
+            }
+
+            async createDiff(request) {
+                await loadMonaco();
+                const container = document.getElementById(this.key);
+                if (!container) {
+                    throw new Error('Container not found');
+                }
+                const options = Object.assign({}, request.options);
+                const language = options.language;
+                delete options.language;
+                const diffEditor = monaco.editor.createDiffEditor(container, options);
+                diffEditor.setModel({
+                    original: monaco.editor.createModel(request.original, language),
+                    modified: monaco.editor.createModel(request.modified, language)
+                });
+                this.diffEditor = diffEditor;
+                this.editor = diffEditor.getModifiedEditor();
+
+                // Edits to the modified side go to Rust like those of a plain editor
+                this.editor.onDidChangeModelContent(() => {
+                    if (!this.applyingRemote) {
+                        this.emit({ event: 'change', value: this.editor.getValue() });
+                    }
+                });
+                diffEditor.onDidUpdateDiff(() => {
+                    this.emit({ event: 'diff', changes: plain(diffEditor.getLineChanges() || []) });
+                });
+
+                console.log('Monaco diff editor created successfully!', this.key);
+                this.emit({ event: 'ready' });
+            }
+
+            syncValue(value) {
+                if (this.editor.getValue() === value) {
+                    return;
+                }
+                this.applyingRemote = true;
+                try {
+                    this.editor.setValue(value);
+                } finally {
+                    this.applyingRemote = false;
+                }
+                // New content drops all decorations, so the declared ranges start over
+                if (this.protectedRanges.length > 0) {
+                    this.markProtected(this.protectedRanges);
+                }
+            }
+
+            setProtectedRanges(ranges) {
+                this.protectedRanges = ranges;
+                this.markProtected(ranges);
+            }
+
+            markProtected(ranges) {
+                ensureProtectedStyle();
+                const model = this.editor.getModel();
+                const previous = this.protectedModel === model ? this.protectedIds : [];
+                this.protectedIds = model.deltaDecorations(previous, ranges.map((range) => ({
+                    range,
+                    options: {
+                        className: 'monaco-protected-range',
+                        linesDecorationsClassName: 'monaco-protected-lines',
+                        stickiness: monaco.editor.TrackedRangeStickiness.NeverGrowsWhenTypingAtEdges,
+                        hoverMessage: { value: 'Read-only' }
+                    }
+                })));
+                this.protectedModel = model;
+                this.rememberProtected(model);
+            }
+
+            rememberProtected(model) {
+                this.protectedBefore = this.protectedIds.map((id) => model.getDecorationRange(id)).filter(Boolean);
+                this.protectedValue = this.protectedBefore.length > 0 ? model.getValue() : '';
+            }
+
+            // Takes back an edit that reached into a protected range; true if it had to.
+            // Edits that only touch a range's edges, or put back the text that was there, are fine.
+            //
+            // Undo and redo come through here like typing does, so they cannot bring protected
+            // text back either. They normally have nothing to take back: the inverse goes on the
+            // undo stack inside this event, into the element still open for the user's edit, so
+            // undoing that element replaces the protected text with itself.
+            rejectProtectedEdit(event) {
+                const model = this.editor.getModel();
+                if (this.protectedModel !== model || this.protectedBefore.length === 0) {
+                    return false;
+                }
+                if (this.editingFromRust) {
+                    // setValue drops the decorations, as in syncValue
+                    if (event.isFlush) {
+                        this.markProtected(this.protectedRanges);
+                    } else {
+                        this.rememberProtected(model);
+                    }
+                    return false;
+                }
+                const before = this.protectedBefore;
+                const inside = (change, range) => {
+                    if (change.text === this.protectedValue.substr(change.rangeOffset, change.rangeLength)) {
+                        return false;
+                    }
+                    return change.rangeLength === 0
+                        ? monaco.Range.strictContainsPosition(range, monaco.Range.getStartPosition(change.range))
+                        : monaco.Range.areIntersecting(change.range, range);
+                };
+                if (!event.changes.some((change) => before.some((range) => inside(change, range)))) {
+                    this.rememberProtected(model);
+                    return false;
+                }
+                // The changes are in the coordinates of the old text, so each one's
+                // replacement starts where the earlier ones' have shifted it to
+                const changes = event.changes.slice().sort((a, b) => a.rangeOffset - b.rangeOffset);
+                let shift = 0;
+                const inverse = changes.map((change) => {
+                    const start = change.rangeOffset + shift;
+                    shift += change.text.length - change.rangeLength;
+                    return {
+                        range: monaco.Range.fromPositions(
+                            model.getPositionAt(start), model.getPositionAt(start + change.text.length)),
+                        text: this.protectedValue.substr(change.rangeOffset, change.rangeLength)
+                    };
+                });
+                // Goes on the undo stack after the edit, so undo and redo keep matching the text
+                this.applyingRemote = true;
+                try {
+                    model.pushEditOperations([], inverse, () => null);
+                } finally {
+                    this.applyingRemote = false;
+                }
+                // The edit may have shrunk the decorations, which taking it back does not regrow
+                this.markProtected(before);
+                return true;
+            }
+
+            openModel(request) {
+                const uri = monaco.Uri.parse(request.uri);
+                let model = monaco.editor.getModel(uri);
+                if (!model) {
+                    model = monaco.editor.createModel(request.value, request.language, uri);
+                    this.models[uri.toString()] = model;
+                }
+                const previous = this.editor.saveViewState();
+                if (this.editor.getModel() !== model) {
+                    // Protected ranges follow the editor to the model it shows
+                    if (this.protectedModel && !this.protectedModel.isDisposed()) {
+                        this.protectedModel.deltaDecorations(this.protectedIds, []);
+                    }
+                    this.protectedModel = null;
+                    this.protectedIds = [];
+                    this.protectedBefore = [];
+                    this.editor.setModel(model);
+                    if (this.protectedRanges.length > 0) {
+                        this.markProtected(this.protectedRanges);
+                    }
+                }
+                if (request.view_state) {
+                    this.editor.restoreViewState(request.view_state);
+                }
+                return previous;
+            }
+
+            closeModel(request) {
+                const key = monaco.Uri.parse(request.uri).toString();
+                const model = this.models[key];
+                if (model && this.editor.getModel() !== model) {
+                    delete this.models[key];
+                    model.dispose();
+                }
+            }
+
+            setProviders(language, specs) {
+                this.providers = {};
+                for (const spec of specs) {
+                    ensureRegistered(language, spec);
+                    this.providers[spec.kind] = spec;
+                }
+            }
+
+            // Asks Rust through a provide event; resolves with null if Monaco cancels first
+            provide(query, token) {
+                const request = ++this.nextRequest;
+                return new Promise((resolve) => {
+                    this.pending[request] = resolve;
+                    if (token) {
+                        // Rust may not have started on it yet, so tell it to skip the work
+                        token.onCancellationRequested(() => {
+                            if (this.pending[request]) {
+                                this.settle(request, null);
+                                this.emit({ event: 'cancel', request });
+                            }
+                        });
+                    }
+                    this.emit({ event: 'provide', request, query });
+                });
+            }
+
+            settle(request, result) {
+                const resolve = this.pending[request];
+                if (resolve) {
+                    delete this.pending[request];
+                    resolve(result);
+                }
+            }
+
+            async perform(request) {
+                switch (request.op) {
+                    case 'create':
+                    case 'create_diff':
+                        try {
+                            return await (request.op === 'create' ? this.create(request) : this.createDiff(request));
+                        } catch (error) {
+                            this.emit(Object.assign({ event: 'error' }, errorFields(error)));
+                            throw error;
+                        }
+                    case 'register_language':
+                        await loadMonaco();
+                        return registerLanguage(request.definition);
+                    case 'sync_value':
+                        return this.syncValue(request.value);
+                    case 'call': {
+                        const target = this.target(request.target);
+                        if (typeof target[request.method] !== 'function') {
+                            throw new Error(request.target + '.' + request.method + ' is not a function');
+                        }
+                        // The editing methods apply their change before returning
+                        this.editingFromRust = true;
+                        let result;
+                        try {
+                            result = target[request.method](...this.resolve(request.args));
+                        } finally {
+                            this.editingFromRust = false;
+                        }
+                        return await result;
+                    }
+                    case 'set_providers':
+                        return this.setProviders(request.language, request.providers);
+                    case 'set_protected_ranges':
+                        return this.setProtectedRanges(request.ranges);
+                    case 'open_model':
+                        return this.openModel(request);
+                    case 'close_model':
+                        return this.closeModel(request);
+                    case 'resolve':
+                        return this.settle(request.request, request.result);
+                }
+                throw new Error('Unknown op: ' + request.op);
+            }
+
+            async handle(request) {
+                if (request.v !== VERSION) {
+                    this.respond(request.id, Object.assign({ status: 'exception' },
+                        errorFields(new Error('Protocol mismatch: expected v' + VERSION + ', got v' + request.v))));
+                    return;
+                }
+                if (this.disposed) {
+                    this.respond(request.id, { status: 'disposed' });
+                    return;
+                }
+                const creates = request.op === 'create' || request.op === 'create_diff';
+                if (!creates && request.op !== 'register_language' && !this.editor) {
+                    this.respond(request.id, { status: 'not_initialized' });
+                    return;
+                }
+                try {
+                    const value = await this.perform(request);
+                    this.respond(request.id, { status: 'ok', value: plain(value) });
+                } catch (error) {
+                    this.respond(request.id, Object.assign({ status: 'exception' }, errorFields(error)));
+                }
+            }
+
+            async run() {
+                while (!this.disposed) {
+                    const request = await this.channel.recv();
+                    this.queue = this.queue.then(() => this.handle(request));
+                }
+            }
+
+            dispose() {
+                this.disposed = true;
+                for (const request of Object.keys(this.pending)) {
+                    this.settle(request, null);
+                }
+                if (this.diffEditor) {
+                    // The diff editor does not own the models it was given
+                    const models = this.diffEditor.getModel();
+                    this.diffEditor.dispose();
+                    models.original.dispose();
+                    models.modified.dispose();
+                    this.diffEditor = null;
+                    this.editor = null;
+                } else if (this.editor) {
+                    this.editor.dispose();
+                    this.editor = null;
+                }
+                for (const model of Object.values(this.models)) {
+                    model.dispose();
+                }
+                this.models = {};
+            }
+        }
+
+        return {
+            version: VERSION,
+            instances,
+            run(key, channel) {
+                const instance = new Instance(key, channel);
+                instances[key] = instance;
+                return instance.run();
+            },
+            dispose(key) {
+                if (instances[key]) {
+                    instances[key].dispose();
+                    delete instances[key];
+                }
+            }
+        };
+    })();
+}
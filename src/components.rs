@@ -1,2 +1,10 @@
+pub mod diff_editor;
 pub mod editor;
-pub use editor::MonacoEditor;
\ No newline at end of file
+pub mod editor_tabs;
+pub mod merge_editor;
+pub mod outline;
+pub use diff_editor::MonacoDiffEditor;
+pub use editor::{EditorStatus, MonacoEditor};
+pub use editor_tabs::{EditorTab, EditorTabs};
+pub use merge_editor::MergeEditor;
+pub use outline::OutlinePanel;
@@ -0,0 +1,187 @@
+use dioxus::prelude::*;
+use futures::channel::mpsc;
+use serde_json::{json, Map, Value};
+use std::sync::atomic::Ordering;
+
+use super::editor::{create_options, EditorStatus, NEXT_EDITOR_ID};
+use crate::monaco::bridge::{self, Bridge};
+use crate::monaco::protocol::{Event, ObjectRef, Op, Target};
+use crate::monaco::{EditorOptions, EditorTheme, LineChange, MonacoDiffHandle, MonacoError, MonacoHandle};
+
+/// Two versions of a text next to each other, built on `monaco.editor.createDiffEditor`.
+#[component]
+pub fn MonacoDiffEditor(
+    /// The text before the change. Changing it replaces the original side.
+    #[props(into)]
+    original: String,
+    /// The text after the change. Changing it replaces the modified side, unless it
+    /// is what the editor already shows.
+    #[props(into)]
+    modified: String,
+    /// Lets the user edit the modified side; edits are reported through `onchange`.
+    #[props(default)]
+    editable: bool,
+    /// Called with the modified side's full content every time it is edited.
+    onchange: Option<EventHandler<String>>,
+    /// Shows both versions in one column, with removed lines above added ones,
+    /// instead of side by side.
+    #[props(default)]
+    inline: bool,
+    /// Monaco language id of both sides.
+    #[props(into, default = "javascript".to_string())]
+    language: String,
+    /// Editor options for both sides, applied with `updateOptions` when they change.
+    #[props(default = EditorOptions::snippet())]
+    options: EditorOptions,
+    /// Built-in or custom theme. Monaco has a single theme per page.
+    #[props(into, default)]
+    theme: EditorTheme,
+    /// Called once the diff editor has been created, with a handle for driving it from Rust.
+    onready: Option<EventHandler<MonacoDiffHandle>>,
+    /// Called with the changed blocks every time Monaco has recomputed the diff.
+    ondiff: Option<EventHandler<Vec<LineChange>>>,
+    /// Called when Monaco could not be loaded, the editor could not be created, or its channel broke down.
+    onerror: Option<EventHandler<MonacoError>>,
+    /// Receives the editor's lifecycle status.
+    status: Option<Signal<EditorStatus>>,
+) -> Element {
+    let editor_id = use_hook(|| format!("monaco-diff-editor-{}", NEXT_EDITOR_ID.fetch_add(1, Ordering::Relaxed)));
+    // Last modified content both sides agree on; used to keep echoes from bouncing back and forth
+    let mut last_synced = use_signal(|| modified.clone());
+    let local_status = use_signal(|| EditorStatus::Loading);
+    let editor_status = status.unwrap_or(local_status);
+    let mut current_original = use_signal(|| original.clone());
+    let mut current_language = use_signal(|| language.clone());
+    let mut current_options = use_signal(|| options.clone());
+    let mut current_theme = use_signal(|| theme.clone());
+    let mut current_layout = use_signal(|| (editable, inline));
+
+    let handle = use_hook(|| {
+        let (tx, mut rx) = mpsc::unbounded();
+        let handle = MonacoHandle::new(editor_id.clone(), tx);
+        let ready_handle = MonacoDiffHandle::new(handle.clone());
+        let id = editor_id.clone();
+
+        spawn(async move {
+            let ready = || {
+                if let Some(handler) = onready {
+                    handler.call(ready_handle.clone());
+                }
+            };
+            let setup = move |bridge: &mut Bridge| {
+                let theme = current_theme.peek().clone();
+                let (editable, inline) = *current_layout.peek();
+                let mut options = create_options(&current_language.peek(), &current_options.peek(), &theme);
+                if let Some(options) = options.as_object_mut() {
+                    options.extend(layout_options(editable, inline));
+                }
+                let create = Op::CreateDiff {
+                    original: current_original.peek().clone(),
+                    modified: last_synced.peek().clone(),
+                    options,
+                };
+                bridge.send(create, None);
+                if let EditorTheme::Custom(_) = theme {
+                    for op in theme.apply_ops() {
+                        bridge.send(op, None);
+                    }
+                }
+            };
+
+            let on_event = |_: &mut Bridge, event: Event| match event {
+                Event::Change { value } => {
+                    last_synced.set(value.clone());
+                    if let Some(handler) = onchange {
+                        handler.call(value);
+                    }
+                }
+                Event::Diff { changes } => {
+                    if let Some(handler) = ondiff {
+                        handler.call(changes);
+                    }
+                }
+                // A diff editor serves no providers or commands
+                _ => {}
+            };
+            bridge::run(&id, &mut rx, editor_status, ready, onerror, setup, on_event).await;
+        });
+
+        handle
+    });
+
+    // Replace the modified side when the prop moves away from what the editor shows
+    let modified_handle = handle.clone();
+    use_effect(use_reactive!(|modified| {
+        if *last_synced.peek() != modified {
+            last_synced.set(modified.clone());
+            modified_handle.notify(Op::SyncValue { value: modified });
+        }
+    }));
+
+    let original_handle = handle.clone();
+    use_effect(use_reactive!(|original| {
+        if *current_original.peek() != original {
+            original_handle.notify(Op::call(Target::OriginalModel, "setValue", [json!(original)]));
+            current_original.set(original);
+        }
+    }));
+
+    // Both models follow the language
+    let language_handle = handle.clone();
+    use_effect(use_reactive!(|language| {
+        if *current_language.peek() != language {
+            for model in [ObjectRef::OriginalModel, ObjectRef::Model] {
+                let args = [model.arg(), json!(language)];
+                language_handle.notify(Op::call(Target::MonacoEditor, "setModelLanguage", args));
+            }
+            current_language.set(language);
+        }
+    }));
+
+    let options_handle = handle.clone();
+    use_effect(use_reactive!(|options, editable, inline| {
+        if *current_options.peek() != options {
+            options_handle.notify(Op::call(Target::DiffEditor, "updateOptions", [options.to_json()]));
+            current_options.set(options);
+        }
+        if *current_layout.peek() != (editable, inline) {
+            let layout = Value::Object(layout_options(editable, inline));
+            options_handle.notify(Op::call(Target::DiffEditor, "updateOptions", [layout]));
+            current_layout.set((editable, inline));
+        }
+    }));
+
+    let theme_handle = handle.clone();
+    use_effect(use_reactive!(|theme| {
+        if *current_theme.peek() != theme {
+            for op in theme.apply_ops() {
+                theme_handle.notify(op);
+            }
+            current_theme.set(theme);
+        }
+    }));
+
+    // Cleanup when component is dropped -- only this instance is disposed
+    let id = editor_id.clone();
+    use_drop(move || Bridge::dispose(&id));
+
+    rsx! {
+        div {
+            id: "{editor_id}",
+            class: "monaco-editor-container",
+            style: "height: 45vh; min-height: 300px; width: 100%; border: 1px solid #444; overflow: hidden; position: relative;",
+            onmounted: move |_| handle.mount(),
+        }
+    }
+}
+
+/// The diff options behind the `editable` and `inline` props.
+fn layout_options(editable: bool, inline: bool) -> Map<String, Value> {
+    Map::from_iter([
+        ("readOnly".to_string(), json!(!editable)),
+        ("originalEditable".to_string(), json!(false)),
+        ("renderSideBySide".to_string(), json!(!inline)),
+        // Otherwise Monaco switches to inline on its own in narrow containers
+        ("useInlineViewWhenSpaceIsLimited".to_string(), json!(false)),
+    ])
+}
@@ -1,295 +1,350 @@
 use dioxus::prelude::*;
-use dioxus_document as document;
-use serde_json;
+use futures::channel::mpsc;
+use serde_json::json;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::monaco::bridge::{self, Bridge};
+use crate::monaco::diagnostics::set_markers_op;
+use crate::monaco::protocol::{Event, ObjectRef, Op, Target};
+use crate::monaco::{
+    Commands, EditorOptions, EditorTheme, LanguageDefinition, MonacoError, MonacoHandle, Providers, Range, Validator,
+};
+
+// Each mounted editor gets its own container id and its own instance in the dispatcher.
+pub(crate) static NEXT_EDITOR_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Lifecycle of a single editor instance, as reported by the dispatcher.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditorStatus {
+    /// Monaco is still loading or the editor has not been created yet.
+    Loading,
+    /// The editor exists and accepts commands.
+    Ready,
+    /// Loading Monaco or creating the editor failed.
+    Failed(String),
+}
 
 #[component]
-pub fn MonacoEditor(initial_value: String) -> Element {
-    let editor_text = use_signal(|| initial_value.clone());
-    let mut editor_ready = use_signal(|| false);
+pub fn MonacoEditor(
+    #[props(default)] initial_value: String,
+    /// Two-way bound content: edits in Monaco are written here, and writes from Rust show up in the editor.
+    value: Option<Signal<String>>,
+    /// Called with the full content every time it changes in the editor.
+    onchange: Option<EventHandler<String>>,
+    /// Called once the editor has been created, with a handle for driving it from Rust.
+    onready: Option<EventHandler<MonacoHandle>>,
+    /// Called when Monaco could not be loaded, the editor could not be created, or its channel broke down.
+    onerror: Option<EventHandler<MonacoError>>,
+    /// Receives the editor's lifecycle status.
+    status: Option<Signal<EditorStatus>>,
+    /// Monaco language id, see [`language_for_path`](crate::monaco::language_for_path), or the
+    /// id of one of `languages`.
+    /// Changing it re-highlights the current model in place.
+    #[props(into, default = "javascript".to_string())]
+    language: String,
+    /// Editor options. Changes are applied to the live editor with `updateOptions`.
+    #[props(default = EditorOptions::snippet())]
+    options: EditorOptions,
+    /// Keeps the user from editing anything; Rust can still write the value.
+    #[props(default)]
+    read_only: bool,
+    /// Ranges the user cannot edit, such as a template's fixed header and footer. They
+    /// are shaded, move along when edits elsewhere shift their lines, and edits reaching
+    /// into them are taken back; typing right next to them is fine. Edits made through
+    /// [`MonacoHandle`] are Rust's own and may change them. Changed ranges, values
+    /// written from Rust, and switching models start over from these coordinates.
+    #[props(default)]
+    protected_ranges: Vec<Range>,
+    /// Languages defined in Rust, registered before the editor is created. Changed
+    /// definitions are re-registered in place.
+    #[props(default)]
+    languages: Vec<LanguageDefinition>,
+    /// Built-in or custom theme; custom ones are registered with `defineTheme` first.
+    /// Monaco has a single theme per page, so this affects every editor on it.
+    #[props(into, default)]
+    theme: EditorTheme,
+    /// Checks the content once typing pauses; its diagnostics are shown as markers.
+    #[props(into)]
+    validator: Option<Validator>,
+    /// How long typing has to pause before the validator runs.
+    #[props(default = 300)]
+    validation_delay_ms: u64,
+    /// Language features answered from Rust for this editor's language.
+    #[props(default)]
+    providers: Providers,
+    /// Rust handlers for commands run in the editor, such as the ones behind code lenses.
+    #[props(default)]
+    commands: Commands,
+) -> Element {
+    let editor_id = use_hook(|| format!("monaco-editor-{}", NEXT_EDITOR_ID.fetch_add(1, Ordering::Relaxed)));
+    let local_value = use_signal(|| initial_value.clone());
+    let mut editor_text = value.unwrap_or(local_value);
+    // Last content both sides agree on; used to keep echoes from bouncing back and forth
+    let mut last_synced = use_signal(|| editor_text.peek().clone());
+    let local_status = use_signal(|| EditorStatus::Loading);
+    let editor_status = status.unwrap_or(local_status);
+    let mut current_language = use_signal(|| language.clone());
+    let mut current_options = use_signal(|| options.clone());
+    let mut current_read_only = use_signal(|| read_only);
+    let mut current_protected = use_signal(|| protected_ranges.clone());
+    let mut current_languages = use_signal(|| languages.clone());
+    let mut current_theme = use_signal(|| theme.clone());
+    let mut current_providers = use_signal(|| providers.clone());
+    let mut current_commands = use_signal(|| commands.clone());
+
+    let handle = use_hook(|| {
+        let (tx, mut rx) = mpsc::unbounded();
+        let handle = MonacoHandle::new(editor_id.clone(), tx);
+        let ready_handle = handle.clone();
+        let id = editor_id.clone();
 
-    // Function to get current editor content
-    let get_editor_content = move |_: Event<MouseData>| {
-        println!("Button click detected");
-        let mut editor_text_clone = editor_text.clone();
         spawn(async move {
-            println!("Attempting to get editor content...");
-            
-            // First verify editor exists and is ready
-            let check_js = r#"
-                if (typeof window.monaco_editor === 'undefined') {
-                    console.log('Editor not initialized');
-                    return 'not_initialized';
+            let ready = || {
+                if let Some(handler) = onready {
+                    handler.call(ready_handle.clone());
                 }
-                if (!window.monaco_editor) {
-                    console.log('Editor object is null');
-                    return 'null';
+            };
+            let setup = move |bridge: &mut Bridge| {
+                // Queued ahead of the create, so the model can start out in a custom language
+                for definition in current_languages.peek().iter() {
+                    bridge.send(Op::RegisterLanguage { definition: Box::new(definition.clone()) }, None);
                 }
-                if (typeof window.monaco_editor.getValue !== 'function') {
-                    console.log('getValue not available');
-                    return 'no_getValue';
+                let theme = current_theme.peek().clone();
+                let mut options = create_options(
+                    &current_language.peek(),
+                    &with_provider_defaults(&current_options.peek(), &current_providers.peek()),
+                    &theme,
+                );
+                if let (true, Some(options)) = (*current_read_only.peek(), options.as_object_mut()) {
+                    options.insert("readOnly".to_string(), json!(true));
                 }
-                console.log('Editor is ready');
-                return 'ready';
-            "#;
-            
-            match document::eval(check_js).await {
-                Ok(status) => {
-                    println!("Editor status check: {}", status);
-                    if status.as_str() != Some("ready") {
-                        println!("Editor not ready, status: {}", status);
-                        return;
+                bridge.send(Op::Create { value: editor_text.peek().clone(), options }, None);
+                if !current_protected.peek().is_empty() {
+                    bridge.send(Op::SetProtectedRanges { ranges: current_protected.peek().clone() }, None);
+                }
+                // Custom themes can only be defined once Monaco is loaded, which the create takes care of
+                if let EditorTheme::Custom(_) = theme {
+                    for op in theme.apply_ops() {
+                        bridge.send(op, None);
                     }
                 }
-                Err(e) => {
-                    println!("Editor check failed: {}", e);
-                    return;
+                if !current_providers.peek().is_empty() {
+                    bridge.send(current_providers.peek().register_op(&current_language.peek()), None);
                 }
-            }
+            };
 
-            // Now try to get content
-            let get_js = r#"
-                try {
-                    if (!window.monaco_editor) {
-                        console.error('Editor missing in getValue');
-                        return null;
+            // Queries waiting for typing to pause, by request; each task removes itself once answered
+            let deferred = Rc::new(RefCell::new(HashMap::<u64, Task>::new()));
+            let on_event = |bridge: &mut Bridge, event: Event| match event {
+                Event::Change { value } => {
+                    last_synced.set(value.clone());
+                    editor_text.set(value.clone());
+                    if let Some(handler) = onchange {
+                        handler.call(value);
                     }
-                    console.log('Editor object:', window.monaco_editor);
-                    const model = window.monaco_editor.getModel();
-                    console.log('Editor model:', model);
-                    const content = window.monaco_editor.getValue();
-                    console.log('Raw content:', content);
-                    return content || null;
-                } catch (e) {
-                    console.error('Error getting content:', e);
-                    return String(e);
                 }
-            "#;
-            
-            match document::eval(get_js).await {
-                Ok(content) => {
-                    println!("Content result: {}", content);
-                    if let Some(text) = content.as_str() {
-                        println!("Got text: {}", text);
-                        editor_text_clone.set(text.to_string());
+                Event::Provide { request, query } => {
+                    let Some(delay) = current_providers.peek().delay(&query) else {
+                        let result = current_providers.peek().answer(&editor_text.peek(), &query);
+                        bridge.send(Op::Resolve { request, result }, None);
+                        return;
+                    };
+                    // Stale queries are cancelled by Monaco before the wait is over
+                    let resolve_handle = ready_handle.clone();
+                    let answered = deferred.clone();
+                    let task = spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        let result = current_providers.peek().answer(&editor_text.peek(), &query);
+                        resolve_handle.notify(Op::Resolve { request, result });
+                        answered.borrow_mut().remove(&request);
+                    });
+                    deferred.borrow_mut().insert(request, task);
+                }
+                Event::Cancel { request } => {
+                    if let Some(task) = deferred.borrow_mut().remove(&request) {
+                        task.cancel();
                     }
-                },
-                Err(e) => println!("Get failed: {}", e)
-            }
-        });
-    };
-
-    // Cleanup when component is dropped
-    let _cleanup = use_drop(move || {
-        spawn(async move {
-            let _ = document::eval(
-                r#"
-                try {
-                    if (window.monaco_editor) {
-                        window.monaco_editor.dispose();
-                        window.monaco_editor = null;
+                }
+                Event::Command { id: command, args } => {
+                    // Cloned so a handler can write signals this component reads
+                    let commands = current_commands.peek().clone();
+                    if let (false, Some(handler)) = (commands.run(&command, &args), onerror) {
+                        handler.call(MonacoError::UnhandledCommand(command));
                     }
-                } catch (e) {
-                    console.error('Dispose error', e);
                 }
-                "#,
-            )
-            .await;
+                // Only diff editors report diffs
+                _ => {}
+            };
+            bridge::run(&id, &mut rx, editor_status, ready, onerror, setup, on_event).await;
         });
-    });
-
-    rsx! {
-        div {
-            // Editor container
-            div {
-                id: "monaco-editor-container",
-                style: "height: 45vh; min-height: 300px; width: 100%; border: 1px solid #444; overflow: hidden; position: relative;",
-                onmounted: move |_| {
-                    let initial_value_json = serde_json::to_string(&initial_value).unwrap_or_else(|_| "\"\"".to_string());
 
-                    spawn(async move {
-                        let init_js = format!(r#"
-                            console.log('Initializing Monaco Editor...');
-
-                            (function initMonaco() {{
-                                function createEditor() {{
-                                    const container = document.getElementById('monaco-editor-container');
-                                    if (!container) {{
-                                        console.error('Container not found!');
-                                        return;
-                                    }}
-
-                                    try {{
-                                        window.monaco_editor = monaco.editor.create(container, {{
-                                            value: {initial_value_json},
-                                            language: 'javascript',
-                                            theme: 'vs-dark',
-                                            automaticLayout: true,
-                                            minimap: {{ enabled: false }},
-                                            scrollBeyondLastLine: false,
-                                            fontSize: 14,
-                                            wordWrap: 'on',
-                                            lineNumbers: 'on',
-                                            glyphMargin: false,
-                                            folding: false,
-                                            lineDecorationsWidth: 10,
-                                            lineNumbersMinChars: 0
-                                        }});
+        handle
+    });
 
-                                        console.log('Monaco Editor created successfully!');
-                                        window.monaco_ready = true;
-                                        
-                                        // Set up change listener
-                                        window.monaco_editor.onDidChangeModelContent(() => {{
-                                            console.log('Editor content changed to:', window.monaco_editor.getValue());
-                                        }});
+    // Push writes made on the Rust side into the editor
+    let sync_handle = handle.clone();
+    use_effect(move || {
+        let text = editor_text();
+        if text != *last_synced.peek() {
+            last_synced.set(text.clone());
+            sync_handle.notify(Op::SyncValue { value: text });
+        }
+    });
 
-                                        // Layout after a brief delay
-                                        setTimeout(() => {{
-                                            if (window.monaco_editor) {{
-                                                window.monaco_editor.layout();
-                                            }}
-                                        }}, 100);
-                                    }} catch (error) {{
-                                        console.error('Error creating Monaco editor:', error);
-                                    }}
-                                }}
+    // Register new or changed language definitions ahead of any switch to them
+    let languages_handle = handle.clone();
+    use_effect(use_reactive!(|languages| {
+        if *current_languages.peek() != languages {
+            for definition in languages.iter().filter(|definition| !current_languages.peek().contains(definition)) {
+                languages_handle.notify(Op::RegisterLanguage { definition: Box::new(definition.clone()) });
+            }
+            current_languages.set(languages);
+        }
+    }));
 
-                                if (typeof monaco !== 'undefined' && monaco.editor) {{
-                                    console.log('Monaco already loaded');
-                                    createEditor();
-                                    return;
-                                }}
+    // Switch the model's language when the prop changes
+    let language_handle = handle.clone();
+    use_effect(use_reactive!(|language| {
+        if *current_language.peek() != language {
+            current_language.set(language.clone());
+            language_handle.notify(Op::call(
+                Target::MonacoEditor,
+                "setModelLanguage",
+                [ObjectRef::Model.arg(), json!(language)],
+            ));
+            let providers = current_providers.peek();
+            if !providers.is_empty() {
+                language_handle.notify(providers.register_op(&language));
+            }
+        }
+    }));
 
-                                // Load Monaco from CDN
-                                console.log('Loading Monaco from CDN...');
-                                const cdnScript = document.createElement('script');
-                                cdnScript.src = 'https://cdn.jsdelivr.net/npm/monaco-editor@0.44.0/min/vs/loader.js';
-                                cdnScript.onload = function() {{
-                                    console.log('Monaco loader loaded');
-                                    require.config({{
-                                        paths: {{ 'vs': 'https://cdn.jsdelivr.net/npm/monaco-editor@0.44.0/min/vs' }}
-                                    }});
-                                    require(['vs/editor/editor.main'], function() {{
-                                        console.log('Monaco main loaded');
-                                        createEditor();
-                                    }});
-                                }};
-                                cdnScript.onerror = function() {{
-                                    console.error('Failed to load Monaco from CDN');
-                                }};
-                                document.head.appendChild(cdnScript);
-                            }})();
-                        "#);
+    // Apply option changes to the live editor instead of recreating it
+    let options_handle = handle.clone();
+    use_effect(use_reactive!(|options| {
+        if *current_options.peek() != options {
+            options_handle.notify(Op::call(Target::Editor, "updateOptions", [options.to_json()]));
+            current_options.set(options);
+        }
+    }));
 
-                        if let Err(e) = document::eval(&init_js).await {
-                            println!("Failed to init Monaco: {:?}", e);
-                        }
+    let read_only_handle = handle.clone();
+    use_effect(use_reactive!(|read_only, protected_ranges| {
+        if *current_read_only.peek() != read_only {
+            read_only_handle.notify(Op::call(Target::Editor, "updateOptions", [json!({ "readOnly": read_only })]));
+            current_read_only.set(read_only);
+        }
+        if *current_protected.peek() != protected_ranges {
+            read_only_handle.notify(Op::SetProtectedRanges { ranges: protected_ranges.clone() });
+            current_protected.set(protected_ranges);
+        }
+    }));
 
-                        // More thorough readiness check
-                        spawn(async move {
-                            for i in 0..50 {
-                                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-                                let check_js = r#"
-                                    if (typeof window.monaco_editor === 'undefined') return 'not_initialized';
-                                    if (!window.monaco_editor) return 'null';
-                                    if (typeof window.monaco_editor.getValue !== 'function') return 'not_ready';
-                                    return 'ready';
-                                "#;
-                                match document::eval(check_js).await {
-                                    Ok(status) if status.as_str() == Some("ready") => {
-                                        println!("Monaco editor fully ready after {} attempts", i + 1);
-                                        editor_ready.set(true);
-                                        break;
-                                    }
-                                    Ok(status) => println!("Editor not ready yet: {}", status),
-                                    Err(e) => println!("Check failed: {}", e)
-                                }
-                            }
-                        });
-                    });
-                }
+    // Define and switch themes when the prop changes
+    let theme_handle = handle.clone();
+    use_effect(use_reactive!(|theme| {
+        if *current_theme.peek() != theme {
+            for op in theme.apply_ops() {
+                theme_handle.notify(op);
             }
+            current_theme.set(theme);
+        }
+    }));
 
-            // Controls
-            div {
-                style: "margin-top: 10px; display: flex; gap: 10px; flex-wrap: wrap;",
-                
-                button {
-                    disabled: !editor_ready(),
-                    onclick: get_editor_content,
-                    style: "padding: 8px 16px; background-color: #0078d4; color: white; border: none; border-radius: 4px; cursor: pointer;",
-                    "Get Current Code"
-                }
+    // Serve the new provider set instead of the old one
+    let providers_handle = handle.clone();
+    use_effect(use_reactive!(|providers| {
+        if *current_providers.peek() != providers {
+            providers_handle.notify(providers.register_op(&current_language.peek()));
+            let semantic = providers.has_semantic_tokens();
+            if semantic != current_providers.peek().has_semantic_tokens()
+                && current_options.peek().semantic_highlighting.is_none()
+            {
+                let options = EditorOptions { semantic_highlighting: Some(semantic), ..EditorOptions::default() };
+                providers_handle.notify(Op::call(Target::Editor, "updateOptions", [options.to_json()]));
+            }
+            current_providers.set(providers);
+        }
+    }));
 
-                button {
-                    disabled: !editor_ready(),
-                    onclick: move |_| {
-                        println!("🔄 Set content button clicked");
-                        let mut editor_text_clone = editor_text.clone();
-                        spawn(async move {
-                            println!("📝 Setting new content...");
-                            let new_content = "console.log('New content from Rust!');";
-                            let js = format!(r#"
-                                if (window.monaco_editor) {{
-                                    console.log('Setting new content...');
-                                    window.monaco_editor.setValue("{}");
-                                    console.log('Content set successfully');
-                                    true
-                                }} else {{
-                                    console.warn('Editor not found');
-                                    false
-                                }}"#, new_content);
-                            if let Ok(_) = document::eval(&js).await {
-                                editor_text_clone.set(new_content.to_string());
-                                println!("Set new content in editor");
-                            }
-                        });
-                    },
-                    style: "padding: 8px 16px; background-color: #28a745; color: white; border: none; border-radius: 4px; cursor: pointer;",
-                    "Set Test Content"
-                }
+    use_effect(use_reactive!(|commands| {
+        if *current_commands.peek() != commands {
+            current_commands.set(commands);
+        }
+    }));
 
-                button {
-                    onclick: move |_| {
-                        println!("Current stored content: {}", editor_text());
-                    },
-                    style: "padding: 8px 16px; background-color: #6f42c1; color: white; border: none; border-radius: 4px; cursor: pointer;",
-                    "Print Stored Content"
-                }
+    // Validate once typing pauses; every change restarts the wait
+    let validation_handle = handle.clone();
+    let mut pending_validation = use_signal(|| None::<Task>);
+    // Whether the validator may have left markers on the model
+    let mut has_markers = use_signal(|| false);
+    use_effect(use_reactive!(|validator, validation_delay_ms| {
+        let text = editor_text();
+        let language = current_language();
+        let ready = editor_status() == EditorStatus::Ready;
+        if let Some(task) = pending_validation.take() {
+            task.cancel();
+        }
+        if !ready {
+            return;
+        }
+        let handle = validation_handle.clone();
+        let Some(validator) = validator else {
+            if *has_markers.peek() {
+                handle.notify(set_markers_op(&[]));
+                has_markers.set(false);
             }
+            return;
+        };
+        pending_validation.set(Some(spawn(async move {
+            tokio::time::sleep(Duration::from_millis(validation_delay_ms)).await;
+            let diagnostics = validator.validate(&text, &language);
+            handle.notify(set_markers_op(&diagnostics));
+            has_markers.set(!diagnostics.is_empty());
+        })));
+    }));
 
-            // Live Content section
-            div {
-                style: "margin-top: 20px; border: 1px solid #444; padding: 10px; background-color: #333; border-radius: 4px;",
-                h3 { 
-                    style: "margin: 0 0 10px 0; color: #fff;",
-                    "Stored Content (Length: {editor_text().len()} chars)" 
-                }
-                if editor_ready() {
-                    pre { 
-                        style: "margin: 0; color: #ccc; white-space: pre-wrap; font-family: 'Courier New', monospace; font-size: 12px; max-height: 200px; overflow-y: auto;",
-                        "{editor_text()}" 
-                    }
-                } else {
-                    div {
-                        style: "color: #888; font-style: italic;",
-                        "Monaco Editor loading..."
-                    }
-                }
-            }
+    // Cleanup when component is dropped -- only this instance is disposed
+    let id = editor_id.clone();
+    use_drop(move || Bridge::dispose(&id));
 
-            // Debug section
-            div {
-                style: "margin-top: 10px; padding: 10px; background-color: #222; border-radius: 4px; font-size: 12px;",
-                p { 
-                    style: "margin: 0; color: #888;",
-                    "💡 Click 'Get Current Code' to extract content from Monaco editor"
-                }
-                p { 
-                    style: "margin: 5px 0 0 0; color: #888;",
-                    "🔧 Use 'Print Stored Content' to see what's in the Rust signal"
-                }
-            }
+    rsx! {
+        // Editor container
+        div {
+            id: "{editor_id}",
+            class: "monaco-editor-container",
+            style: "height: 45vh; min-height: 300px; width: 100%; border: 1px solid #444; overflow: hidden; position: relative;",
+            // The dispatcher reports back over the channel once the editor exists
+            onmounted: move |_| handle.mount(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Semantic tokens only show with semantic highlighting on, so a provider for them
+/// turns it on unless the options decide.
+fn with_provider_defaults(options: &EditorOptions, providers: &Providers) -> EditorOptions {
+    let mut options = options.clone();
+    if options.semantic_highlighting.is_none() && providers.has_semantic_tokens() {
+        options.semantic_highlighting = Some(true);
+    }
+    options
+}
+
+/// Options the editor is created with: the fixed ones the component relies on,
+/// overlaid with the caller's.
+pub(crate) fn create_options(language: &str, options: &EditorOptions, theme: &EditorTheme) -> serde_json::Value {
+    let mut create = json!({
+        "language": language,
+        // A custom theme is switched to right after creation, so start out on its base
+        "theme": theme.base().name(),
+        "automaticLayout": true,
+    });
+    if let (Some(create), serde_json::Value::Object(options)) = (create.as_object_mut(), options.to_json()) {
+        create.extend(options);
+    }
+    create
+}
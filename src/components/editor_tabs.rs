@@ -0,0 +1,248 @@
+use dioxus::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use super::editor::MonacoEditor;
+use crate::monaco::language::PLAIN_TEXT;
+use crate::monaco::{
+    language_for_path, Commands, EditorOptions, EditorTheme, LanguageDefinition, MonacoError, MonacoHandle, Providers,
+    Validator, ViewState,
+};
+
+/// A document open in [`EditorTabs`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct EditorTab {
+    /// URI of the tab's model, e.g. `file:///snippets/report.js`. Identifies the tab.
+    pub uri: String,
+    pub value: String,
+    /// Monaco language id; [`EditorTab::new`] picks it from the URI.
+    pub language: String,
+}
+
+impl EditorTab {
+    pub fn new(uri: impl Into<String>, value: impl Into<String>) -> Self {
+        let uri = uri.into();
+        let language = language_for_path(&uri).unwrap_or(PLAIN_TEXT).to_string();
+        Self { uri, value: value.into(), language }
+    }
+
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    /// The last segment of the URI, shown on the tab.
+    pub fn title(&self) -> &str {
+        self.uri.rsplit('/').next().unwrap_or(&self.uri)
+    }
+}
+
+/// One editor with a model per tab, keyed by URI.
+///
+/// Switching tabs keeps each model's undo history, and hands back its cursor,
+/// scroll position and folding as they were when the tab was left.
+#[component]
+pub fn EditorTabs(
+    /// The open documents. Edits are written into the shown tab's `value`, writes
+    /// from Rust show up in its model, and closing a tab removes it.
+    tabs: Signal<Vec<EditorTab>>,
+    /// URI of the tab to show; falls back to the first tab if there is no such tab.
+    active: Option<Signal<String>>,
+    /// Editor options, shared by all tabs.
+    #[props(default = EditorOptions::snippet())]
+    options: EditorOptions,
+    /// Languages defined in Rust, for tabs to use.
+    #[props(default)]
+    languages: Vec<LanguageDefinition>,
+    #[props(into, default)]
+    theme: EditorTheme,
+    /// Checks the shown tab once typing pauses.
+    #[props(into)]
+    validator: Option<Validator>,
+    /// Language features answered from Rust for the shown tab's language.
+    #[props(default)]
+    providers: Providers,
+    #[props(default)]
+    commands: Commands,
+    /// Called once the editor has been created, with a handle for driving it from Rust.
+    onready: Option<EventHandler<MonacoHandle>>,
+    onerror: Option<EventHandler<MonacoError>>,
+) -> Element {
+    let local_active = use_signal(|| tabs.peek().first().map(|tab| tab.uri.clone()).unwrap_or_default());
+    let mut active = active.unwrap_or(local_active);
+    let mut editor = use_signal(|| None::<MonacoHandle>);
+    // URI of the model the editor shows; empty until the first switch to a tab's model
+    let mut shown = use_signal(String::new);
+    // Models created for tabs, and where each tab was left
+    let mut opened = use_signal(HashSet::<String>::new);
+    let mut view_states = use_signal(HashMap::<String, ViewState>::new);
+    let mut text = use_signal(|| {
+        let tabs = tabs.peek();
+        let tab = tabs.iter().find(|tab| tab.uri == *active.peek()).or(tabs.first());
+        tab.map(|tab| tab.value.clone()).unwrap_or_default()
+    });
+
+    let report = move |error: MonacoError| {
+        if let Some(handler) = onerror {
+            handler.call(error);
+        }
+    };
+
+    // Switch models when another tab is picked, or once the editor is ready
+    use_effect(move || {
+        let target = active();
+        let Some(editor) = editor() else { return };
+        if target == shown() {
+            return;
+        }
+        let Some(tab) = tabs.peek().iter().find(|tab| tab.uri == target).cloned() else { return };
+        let view_state = view_states.peek().get(&tab.uri).cloned();
+        spawn(async move {
+            match editor.open_model(&tab.uri, &tab.value, &tab.language, view_state).await {
+                Ok(previous) => {
+                    let left = shown.peek().clone();
+                    if let (false, Some(previous)) = (left.is_empty(), previous) {
+                        view_states.write().insert(left, previous);
+                    }
+                    opened.write().insert(tab.uri.clone());
+                    shown.set(tab.uri);
+                }
+                Err(error) => report(error),
+            }
+        });
+    });
+
+    // The editor's bound value follows the shown tab, including writes made to it from Rust
+    use_effect(move || {
+        let uri = shown();
+        let uri = if uri.is_empty() { active() } else { uri };
+        let value = tabs.read().iter().find(|tab| tab.uri == uri).map(|tab| tab.value.clone());
+        if let Some(value) = value {
+            if *text.peek() != value {
+                text.set(value);
+            }
+        }
+    });
+
+    // Closed tabs give up their models once the editor has moved off them
+    use_effect(move || {
+        let tabs = tabs.read();
+        let showing = shown();
+        if tabs.is_empty() {
+            // The editor goes away with the last tab, and its models with it
+            editor.set(None);
+            return;
+        }
+        if !tabs.iter().any(|tab| tab.uri == *active.peek()) {
+            if let Some(first) = tabs.first() {
+                active.set(first.uri.clone());
+            }
+        }
+        let Some(editor) = editor.peek().clone() else { return };
+        let closed: Vec<String> = opened
+            .peek()
+            .iter()
+            .filter(|uri| **uri != showing && !tabs.iter().any(|tab| tab.uri == **uri))
+            .cloned()
+            .collect();
+        for uri in closed {
+            opened.write().remove(&uri);
+            view_states.write().remove(&uri);
+            let editor = editor.clone();
+            spawn(async move {
+                if let Err(error) = editor.close_model(&uri).await {
+                    report(error);
+                }
+            });
+        }
+    });
+
+    let language = {
+        let uri = shown();
+        let uri = if uri.is_empty() { active() } else { uri };
+        let tabs = tabs.read();
+        tabs.iter().find(|tab| tab.uri == uri).map(|tab| tab.language.clone()).unwrap_or_else(|| PLAIN_TEXT.to_string())
+    };
+
+    rsx! {
+        div {
+            class: "editor-tabs",
+            div {
+                style: "display: flex; overflow-x: auto; background-color: #252526; border: 1px solid #444; border-bottom: none;",
+                for (index, tab) in tabs().into_iter().enumerate() {
+                    div {
+                        key: "{tab.uri}",
+                        style: if tab.uri == active() { ACTIVE_TAB_STYLE } else { TAB_STYLE },
+                        title: "{tab.uri}",
+                        onclick: {
+                            let uri = tab.uri.clone();
+                            move |_| active.set(uri.clone())
+                        },
+                        "{tab.title()}"
+                        span {
+                            style: "margin-left: 8px; color: #888;",
+                            title: "Close",
+                            onclick: move |evt: MouseEvent| {
+                                evt.stop_propagation();
+                                let mut tabs = tabs.write();
+                                // Closing the active tab shows its neighbour
+                                if tabs[index].uri == *active.peek() {
+                                    let after = tabs.get(index + 1);
+                                    let neighbour = after.or(index.checked_sub(1).and_then(|before| tabs.get(before)));
+                                    if let Some(neighbour) = neighbour {
+                                        active.set(neighbour.uri.clone());
+                                    }
+                                }
+                                tabs.remove(index);
+                            },
+                            "×"
+                        }
+                    }
+                }
+            }
+            if tabs.read().is_empty() {
+                div {
+                    style: "height: 45vh; min-height: 300px; border: 1px solid #444; display: flex; align-items: center; justify-content: center; color: #777;",
+                    "No open tabs"
+                }
+            } else {
+                MonacoEditor {
+                    value: text,
+                    language,
+                    options,
+                    languages,
+                    theme,
+                    validator,
+                    providers,
+                    commands,
+                    // Edits belong to the tab whose model is shown, whatever tab is picked meanwhile
+                    onchange: move |value: String| {
+                        let uri = shown.peek().clone();
+                        let index = tabs.peek().iter().position(|tab| tab.uri == uri);
+                        if let Some(index) = index {
+                            if tabs.peek()[index].value != value {
+                                tabs.write()[index].value = value;
+                            }
+                        }
+                    },
+                    onready: move |handle: MonacoHandle| {
+                        // A new editor starts out on a model of its own
+                        shown.set(String::new());
+                        opened.write().clear();
+                        editor.set(Some(handle.clone()));
+                        if let Some(handler) = onready {
+                            handler.call(handle);
+                        }
+                    },
+                    onerror: move |error: MonacoError| {
+                        if let Some(handler) = onerror {
+                            handler.call(error);
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+const TAB_STYLE: &str = "padding: 6px 12px; cursor: pointer; white-space: nowrap; color: #999; background-color: #2d2d2d; border-right: 1px solid #252526;";
+const ACTIVE_TAB_STYLE: &str = "padding: 6px 12px; cursor: pointer; white-space: nowrap; color: #fff; background-color: #1e1e1e; border-right: 1px solid #252526; border-top: 1px solid #0e639c;";
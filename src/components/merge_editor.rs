@@ -0,0 +1,160 @@
+use dioxus::prelude::*;
+
+use super::editor::MonacoEditor;
+use crate::monaco::{
+    conflict_regions, merge, resolve_conflict, ConflictMarkers, EditorTheme, MonacoError, MonacoHandle, Resolution,
+    Validator,
+};
+
+/// The per-conflict buttons, in the order they are shown.
+const RESOLUTIONS: &[(&str, Resolution)] = &[
+    ("Accept ours", Resolution::Ours),
+    ("Accept theirs", Resolution::Theirs),
+    ("Accept both", Resolution::Both),
+    ("Keep base", Resolution::Base),
+];
+
+const PANE_LABEL_STYLE: &str = "padding: 4px 0; color: #bbb; font-size: 11px; text-transform: uppercase;";
+const SMALL_BUTTON_STYLE: &str =
+    "padding: 3px 8px; color: white; background-color: #3a3d41; border: none; border-radius: 3px; cursor: pointer;";
+
+/// Reconciles two edits of the same snippet made from a common `base`.
+///
+/// The three versions are shown read-only above the result, which starts out as
+/// [`merge`]'s output with conflict markers. Conflicts can be resolved with the
+/// buttons or by editing the result by hand; it can only be accepted once no
+/// conflict is left.
+#[component]
+pub fn MergeEditor(
+    /// The version both edits started from.
+    #[props(into)]
+    base: String,
+    #[props(into)]
+    ours: String,
+    #[props(into)]
+    theirs: String,
+    /// Called with the result when the user accepts it.
+    onaccept: EventHandler<String>,
+    /// Monaco language id of all four editors.
+    #[props(into, default = "javascript".to_string())]
+    language: String,
+    #[props(into, default)]
+    theme: EditorTheme,
+    /// Called when one of the editors fails, or a conflict could not be revealed in the result.
+    onerror: Option<EventHandler<MonacoError>>,
+) -> Element {
+    let mut base_text = use_signal(|| base.clone());
+    let mut ours_text = use_signal(|| ours.clone());
+    let mut theirs_text = use_signal(|| theirs.clone());
+    let mut result = use_signal(|| merge(&base, &ours, &theirs).text());
+    let mut result_editor = use_signal(|| None::<MonacoHandle>);
+    let validator = use_hook(|| Validator::new(ConflictMarkers));
+    // The result text is the merge state: whatever markers are left in it are the open conflicts
+    let conflicts = use_memo(move || conflict_regions(&result()));
+
+    // New versions start the merge over
+    use_effect(use_reactive!(|base, ours, theirs| {
+        if *base_text.peek() != base || *ours_text.peek() != ours || *theirs_text.peek() != theirs {
+            result.set(merge(&base, &ours, &theirs).text());
+            base_text.set(base);
+            ours_text.set(ours);
+            theirs_text.set(theirs);
+        }
+    }));
+
+    let open_conflicts = conflicts().len();
+
+    rsx! {
+        div {
+            class: "merge-editor",
+            div {
+                style: "display: flex; gap: 10px;",
+                for (label, text) in [("Base", base_text), ("Ours", ours_text), ("Theirs", theirs_text)] {
+                    div {
+                        key: "{label}",
+                        style: "flex: 1; min-width: 0;",
+                        div { style: PANE_LABEL_STYLE, "{label}" }
+                        MonacoEditor {
+                            value: text,
+                            language: language.clone(),
+                            theme: theme.clone(),
+                            read_only: true,
+                            onerror: move |error: MonacoError| {
+                                if let Some(handler) = onerror {
+                                    handler.call(error);
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+
+            div {
+                style: "margin: 10px 0; display: flex; flex-direction: column; gap: 6px;",
+                div {
+                    style: "color: #bbb;",
+                    if open_conflicts == 0 {
+                        "✔ No conflicts left"
+                    } else {
+                        "⚠ {open_conflicts} conflict(s) left"
+                    }
+                }
+                for (index, region) in conflicts().into_iter().enumerate() {
+                    div {
+                        key: "{index}-{region.start_line_number}",
+                        style: "display: flex; gap: 6px; align-items: center;",
+                        span {
+                            style: "min-width: 110px; color: #f48771; cursor: pointer;",
+                            title: "Show in the result",
+                            onclick: move |_| {
+                                let range = region.range(&result.peek());
+                                if let Some(editor) = result_editor() {
+                                    spawn(async move {
+                                        let revealed = async {
+                                            editor.reveal_range(range).await?;
+                                            editor.set_selection(range).await
+                                        };
+                                        if let (Err(error), Some(handler)) = (revealed.await, onerror) {
+                                            handler.call(error);
+                                        }
+                                    });
+                                }
+                            },
+                            "Conflict at line {region.start_line_number}"
+                        }
+                        for (label, resolution) in RESOLUTIONS.iter().copied() {
+                            button {
+                                style: SMALL_BUTTON_STYLE,
+                                onclick: move |_| {
+                                    let resolved = resolve_conflict(&result.peek(), index, resolution);
+                                    result.set(resolved);
+                                },
+                                "{label}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { style: PANE_LABEL_STYLE, "Result" }
+            MonacoEditor {
+                value: result,
+                language,
+                theme,
+                validator,
+                onready: move |editor: MonacoHandle| result_editor.set(Some(editor)),
+                onerror: move |error: MonacoError| {
+                    if let Some(handler) = onerror {
+                        handler.call(error);
+                    }
+                },
+            }
+            button {
+                style: "margin-top: 10px; padding: 8px 16px; color: white; background-color: #28a745; border: none; border-radius: 4px; cursor: pointer;",
+                disabled: open_conflicts > 0,
+                onclick: move |_| onaccept.call(result()),
+                "Accept merge"
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use dioxus::prelude::*;
+
+use crate::monaco::{DocumentSymbol, MonacoError, MonacoHandle, SymbolKind};
+
+/// Lists document symbols next to a `MonacoEditor` and reveals the one clicked.
+///
+/// The symbols usually come from the same [`DocumentSymbolProvider`](crate::monaco::DocumentSymbolProvider)
+/// the editor uses, run on its bound value.
+#[component]
+pub fn OutlinePanel(
+    symbols: Vec<DocumentSymbol>,
+    /// The editor to reveal symbols in; clicks do nothing until it is ready.
+    editor: Option<MonacoHandle>,
+    /// Called when revealing a symbol in the editor failed.
+    onerror: Option<EventHandler<MonacoError>>,
+) -> Element {
+    rsx! {
+        div {
+            class: "outline-panel",
+            style: "width: 220px; flex-shrink: 0; max-height: 45vh; min-height: 300px; overflow-y: auto; border: 1px solid #444; background-color: #252526; font-size: 13px;",
+            div {
+                style: "padding: 6px 10px; color: #bbb; font-size: 11px; text-transform: uppercase; border-bottom: 1px solid #333;",
+                "Outline"
+            }
+            if symbols.is_empty() {
+                div {
+                    style: "padding: 6px 10px; color: #777; font-style: italic;",
+                    "No symbols"
+                }
+            }
+            for symbol in symbols {
+                OutlineItem { symbol, depth: 0, editor: editor.clone(), onerror }
+            }
+        }
+    }
+}
+
+#[component]
+fn OutlineItem(
+    symbol: DocumentSymbol,
+    depth: usize,
+    editor: Option<MonacoHandle>,
+    onerror: Option<EventHandler<MonacoError>>,
+) -> Element {
+    let target = symbol.selection_range;
+    let reveal_in = editor.clone();
+    let indent = 10 + depth * 14;
+
+    rsx! {
+        div {
+            style: "padding: 3px 10px 3px {indent}px; cursor: pointer; white-space: nowrap; overflow: hidden; text-overflow: ellipsis; color: #ddd;",
+            title: "{symbol.detail}",
+            onclick: move |_| {
+                if let Some(editor) = reveal_in.clone() {
+                    spawn(async move {
+                        let revealed = async {
+                            editor.reveal_range(target).await?;
+                            editor.set_selection(target).await?;
+                            editor.focus().await
+                        };
+                        if let (Err(error), Some(handler)) = (revealed.await, onerror) {
+                            handler.call(error);
+                        }
+                    });
+                }
+            },
+            span { style: "display: inline-block; width: 16px; color: {kind_color(symbol.kind)};", "{kind_icon(symbol.kind)}" }
+            "{symbol.name}"
+        }
+        for child in symbol.children {
+            OutlineItem { symbol: child, depth: depth + 1, editor: editor.clone(), onerror }
+        }
+    }
+}
+
+fn kind_icon(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Function | SymbolKind::Method | SymbolKind::Constructor => "ƒ",
+        SymbolKind::Class | SymbolKind::Struct | SymbolKind::Interface => "◆",
+        SymbolKind::Constant | SymbolKind::EnumMember => "π",
+        SymbolKind::Module | SymbolKind::Namespace | SymbolKind::Package => "▣",
+        _ => "•",
+    }
+}
+
+fn kind_color(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Function | SymbolKind::Method | SymbolKind::Constructor => "#c586c0",
+        SymbolKind::Class | SymbolKind::Struct | SymbolKind::Interface => "#ee9d28",
+        _ => "#75beff",
+    }
+}
@@ -1,13 +1,562 @@
 use dioxus::prelude::*;
-use crate::components::MonacoEditor; // Import from the `components` module
+// Import from the `components` module
+use crate::components::{
+    EditorStatus, EditorTab, EditorTabs, MergeEditor, MonacoDiffEditor, MonacoEditor, OutlinePanel,
+};
+use crate::host_api::{HostApi, DEPRECATED_CODE, DEPRECATED_FUNCTIONS};
+use crate::snippet_links::SnippetLinks;
+use crate::snippet_outline::SnippetOutline;
+use crate::monaco::language::PLAIN_TEXT;
+use crate::monaco::{
+    language_for_path, BaseTheme, CodeLens, CodeLensProvider, Commands, Diagnostic, DocumentSymbolProvider,
+    EditorOptions, EditorTheme, LanguageDefinition, LineChange, MonacoDiffHandle, MonacoError, MonacoHandle, MonacoTheme,
+    Providers, Range, Severity, SnippetValidator, Validator,
+};
+
+// File names offered for the second editor; the language follows the extension
+const SNIPPET_FILES: &[&str] =
+    &["snippet.js", "query.sql", "script.py", "settings.yaml", "cleanup.lua", "nightly.pipeline", "notes.txt"];
+
+// The host app's own DSL, declared for Monaco in TOML
+const PIPELINE_LANGUAGE: &str = include_str!("../assets/languages/pipeline.toml");
+
+// Brand theme in VS Code's format, converted when the page is built
+const SNIPPET_NIGHT: &str = include_str!("../assets/themes/snippet-night.json");
+
+// Themes offered in the picker; Monaco applies the theme to every editor on the page
+const THEMES: &[&str] = &["vs-dark", "vs", "hc-black", "snippet-night"];
+
+// A hook the host app generates; only the body belongs to the user
+const ORDER_HOOK_TEMPLATE: &str =
+    "// Generated by the host app, only the body is yours\nexport function onOrder(order) {\n  host.log(order.id);\n}";
+
+// Two people's edits of the same snippet, for the merge demo
+const MERGE_BASE: &str = "let total = 0;\nfor (const item of items) {\n  total += item.price;\n}\nconsole.log(total);";
+const MERGE_OURS: &str =
+    "let total = 0;\nfor (const item of items) {\n  total += item.price * item.count;\n}\nhost.log(total);";
+const MERGE_THEIRS: &str =
+    "const total = 0;\nfor (const item of items) {\n  total += item.price - item.discount;\n}\nhost.log(total);";
+
+const BUTTON_STYLE: &str = "padding: 8px 16px; color: white; border: none; border-radius: 4px; cursor: pointer;";
+
+// Rules a snippet has to follow before the host app runs it
+struct SnippetRules;
+
+impl SnippetValidator for SnippetRules {
+    fn validate(&self, source: &str, _language: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index as u32 + 1;
+            let mut flag = |needle: &str, severity: Severity, message: &str, code: &str| {
+                for (offset, _) in line.match_indices(needle) {
+                    // Monaco counts columns in UTF-16 code units
+                    let start_column = line[..offset].encode_utf16().count() as u32 + 1;
+                    let range = Range {
+                        start_line_number: line_number,
+                        start_column,
+                        end_line_number: line_number,
+                        end_column: start_column + needle.encode_utf16().count() as u32,
+                    };
+                    diagnostics.push(Diagnostic::new(range, severity, message).with_code(code));
+                }
+            };
+            flag("eval(", Severity::Error, "Snippets may not call eval", "no-eval");
+            flag("debugger", Severity::Warning, "Remove debugger statements before saving", "no-debugger");
+            flag("var ", Severity::Info, "Prefer let or const", "no-var");
+            for (old, new) in DEPRECATED_FUNCTIONS {
+                let message = format!("host.{old} is deprecated, use host.{new}");
+                flag(&format!("host.{old}"), Severity::Warning, &message, DEPRECATED_CODE);
+            }
+        }
+        diagnostics
+    }
+}
+
+// "▶ Run" above every top-level function, "Test" above the ones named test*
+struct EntryPoints;
+
+impl CodeLensProvider for EntryPoints {
+    fn provide_code_lenses(&self, source: &str) -> Vec<CodeLens> {
+        source
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let rest = line.strip_prefix("async ").unwrap_or(line).strip_prefix("function ")?;
+                let name: String = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$').collect();
+                if name.is_empty() {
+                    return None;
+                }
+                let line_number = index as u32 + 1;
+                let range =
+                    Range { start_line_number: line_number, start_column: 1, end_line_number: line_number, end_column: 1 };
+                let lens = if name.starts_with("test") {
+                    CodeLens::new(range, "Test", "snippet.test")
+                } else {
+                    CodeLens::new(range, "▶ Run", "snippet.run")
+                };
+                Some(lens.with_argument(name))
+            })
+            .collect()
+    }
+}
 
 #[component]
 pub fn Hero() -> Element {
-    let initial_code = "console.log('Hello from Monaco Editor!');".to_string();
+    let mut snippet = use_signal(|| "console.log('Hello from Monaco Editor!');".to_string());
+    let status = use_signal(|| EditorStatus::Loading);
+    let mut handle = use_signal(|| None::<MonacoHandle>);
+    let mut font_size = use_signal(|| 14.0_f32);
+    let mut second_file = use_signal(|| SNIPPET_FILES[0].to_string());
+    let validator = use_hook(|| Validator::new(SnippetRules));
+    let providers = use_hook(|| {
+        let providers = Providers::new()
+            .completion(HostApi)
+            .hover(HostApi)
+            .signature_help(HostApi)
+            .code_actions(HostApi)
+            .code_lenses(EntryPoints)
+            .document_symbols(SnippetOutline)
+            .rename(SnippetLinks)
+            .definition(SnippetLinks)
+            .inlay_hints(HostApi)
+            .inline_completions(HostApi);
+        // Highlighting from the JavaScript grammar, when it is compiled in
+        #[cfg(feature = "tree-sitter")]
+        let providers = providers.semantic_tokens(crate::monaco::TreeSitterTokens::javascript());
+        providers
+    });
+    let outline = use_memo(move || SnippetOutline.provide_document_symbols(&snippet()));
+    let mut last_command = use_signal(|| None::<String>);
+    let commands = use_hook(|| {
+        let function = |args: &[serde_json::Value]| args.first().and_then(|name| name.as_str()).unwrap_or("?").to_string();
+        Commands::new()
+            .on("snippet.run", move |args| last_command.set(Some(format!("▶ Ran {}()", function(args)))))
+            .on("snippet.test", move |args| last_command.set(Some(format!("✔ Tested {}()", function(args)))))
+    });
+    let mut theme_name = use_signal(|| THEMES[0].to_string());
+    let brand_theme = use_hook(|| match MonacoTheme::from_vscode_json(SNIPPET_NIGHT) {
+        Ok(theme) => Some(theme),
+        Err(e) => {
+            println!("Could not convert the bundled theme: {}", e);
+            None
+        }
+    });
+    let theme = match (theme_name().as_str(), &brand_theme) {
+        ("vs", _) => EditorTheme::from(BaseTheme::Vs),
+        ("hc-black", _) => EditorTheme::from(BaseTheme::HcBlack),
+        ("snippet-night", Some(brand)) => EditorTheme::from(brand.clone()),
+        _ => EditorTheme::default(),
+    };
+    let custom_languages = use_hook(|| match LanguageDefinition::from_toml(PIPELINE_LANGUAGE) {
+        Ok(language) => vec![language],
+        Err(e) => {
+            println!("Could not load the pipeline language: {}", e);
+            Vec::new()
+        }
+    });
+    let second_language = match custom_languages.iter().find(|language| language.matches_path(&second_file())) {
+        Some(language) => language.id.clone(),
+        None => language_for_path(&second_file()).unwrap_or(PLAIN_TEXT).to_string(),
+    };
+    let second_code = [
+        "// A second, independent editor instance",
+        "function formatPrice(value) {",
+        "  return '$' + value.toFixed(2);",
+        "}",
+        "",
+    ]
+    .join("\n");
+    let second_providers = use_hook(|| Providers::new().rename(SnippetLinks).definition(SnippetLinks));
+    // The version of the first snippet that is live, reviewed against the one being edited
+    let mut published = use_signal(|| snippet.peek().clone());
+    let mut review = use_signal(|| None::<MonacoDiffHandle>);
+    let mut review_inline = use_signal(|| false);
+    let mut unpublished_changes = use_signal(|| 0_usize);
+    // Snippets kept open side by side in tabs
+    let mut open_tabs = use_signal(|| {
+        vec![
+            EditorTab::new("file:///snippets/report.js", "function report(items) {\n  return items.length;\n}\n"),
+            EditorTab::new(
+                "file:///snippets/totals.sql",
+                "SELECT customer, SUM(price)\nFROM orders\nGROUP BY customer;\n",
+            ),
+            EditorTab::new("file:///snippets/nightly.pipeline", "pipeline nightly {\n  step build every 24h\n}\n")
+                .with_language("pipeline"),
+        ]
+    });
+    let mut active_tab = use_signal(|| "file:///snippets/report.js".to_string());
+    let mut untitled = use_signal(|| 0_usize);
+    let mut template_locked = use_signal(|| false);
+
     rsx! {
         div {
             id: "hero",
-            MonacoEditor { initial_value: initial_code }
+            div {
+                style: "width: 100%;",
+                div {
+                    style: "margin-bottom: 10px;",
+                    "Theme: "
+                    select {
+                        value: "{theme_name}",
+                        onchange: move |event| theme_name.set(event.value()),
+                        for name in THEMES {
+                            option { value: *name, "{name}" }
+                        }
+                    }
+                }
+                div {
+                    style: "display: flex; gap: 10px;",
+                    div {
+                        style: "flex: 1; min-width: 0;",
+                        MonacoEditor {
+                            value: snippet,
+                            status,
+                            theme: theme.clone(),
+                            validator,
+                            providers,
+                            commands,
+                            options: EditorOptions { font_size: Some(font_size()), ..EditorOptions::snippet() },
+                            onready: move |editor: MonacoHandle| {
+                                println!("First editor ready ({})", editor.id());
+                                handle.set(Some(editor));
+                            },
+                            onerror: move |error: MonacoError| println!("First editor error: {}", error),
+                        }
+                    }
+                    OutlinePanel { symbols: outline(), editor: handle() }
+                }
+
+                // Controls
+                div {
+                    style: "margin-top: 10px; display: flex; gap: 10px; flex-wrap: wrap;",
+
+                    button {
+                        disabled: handle().is_none(),
+                        onclick: move |_| {
+                            if let Some(editor) = handle() {
+                                spawn(async move {
+                                    match editor.get_value().await {
+                                        Ok(text) => println!("Editor content: {}", text),
+                                        Err(e) => println!("Get failed: {}", e),
+                                    }
+                                });
+                            }
+                        },
+                        style: "{BUTTON_STYLE} background-color: #0078d4;",
+                        "Get Current Code"
+                    }
+
+                    button {
+                        disabled: handle().is_none(),
+                        onclick: move |_| {
+                            // Writing the signal is enough, the editor picks it up
+                            snippet.set("console.log('New content from Rust!');".to_string());
+                        },
+                        style: "{BUTTON_STYLE} background-color: #28a745;",
+                        "Set Test Content"
+                    }
+
+                    button {
+                        disabled: handle().is_none(),
+                        onclick: move |_| {
+                            if let Some(editor) = handle() {
+                                spawn(async move {
+                                    if let Err(e) = editor.insert_at_cursor("/* inserted from Rust */").await {
+                                        println!("Insert failed: {}", e);
+                                    }
+                                    let _ = editor.focus().await;
+                                });
+                            }
+                        },
+                        style: "{BUTTON_STYLE} background-color: #17a2b8;",
+                        "Insert at Cursor"
+                    }
+
+                    button {
+                        disabled: handle().is_none(),
+                        onclick: move |_| {
+                            if let Some(editor) = handle() {
+                                spawn(async move {
+                                    if let Err(e) = editor.undo().await {
+                                        println!("Undo failed: {}", e);
+                                    }
+                                });
+                            }
+                        },
+                        style: "{BUTTON_STYLE} background-color: #6c757d;",
+                        "Undo"
+                    }
+
+                    button {
+                        disabled: handle().is_none(),
+                        onclick: move |_| {
+                            if let Some(editor) = handle() {
+                                spawn(async move {
+                                    if let Err(e) = editor.redo().await {
+                                        println!("Redo failed: {}", e);
+                                    }
+                                });
+                            }
+                        },
+                        style: "{BUTTON_STYLE} background-color: #6c757d;",
+                        "Redo"
+                    }
+
+                    button {
+                        onclick: move |_| font_size -= 1.0,
+                        style: "{BUTTON_STYLE} background-color: #444;",
+                        "A-"
+                    }
+
+                    button {
+                        onclick: move |_| font_size += 1.0,
+                        style: "{BUTTON_STYLE} background-color: #444;",
+                        "A+"
+                    }
+
+                    button {
+                        onclick: move |_| {
+                            println!("Current stored content: {}", snippet());
+                        },
+                        style: "{BUTTON_STYLE} background-color: #6f42c1;",
+                        "Print Stored Content"
+                    }
+                }
+
+                // Live Content section
+                div {
+                    style: "margin-top: 20px; border: 1px solid #444; padding: 10px; background-color: #333; border-radius: 4px;",
+                    h3 {
+                        style: "margin: 0 0 10px 0; color: #fff;",
+                        "Stored Content (Length: {snippet().len()} chars)"
+                    }
+                    match status() {
+                        EditorStatus::Ready => rsx! {
+                            pre {
+                                style: "margin: 0; color: #ccc; white-space: pre-wrap; font-family: 'Courier New', monospace; font-size: 12px; max-height: 200px; overflow-y: auto;",
+                                "{snippet()}"
+                            }
+                        },
+                        EditorStatus::Loading => rsx! {
+                            div {
+                                style: "color: #888; font-style: italic;",
+                                "Monaco Editor loading..."
+                            }
+                        },
+                        EditorStatus::Failed(reason) => rsx! {
+                            div {
+                                style: "color: #e57373; font-style: italic;",
+                                "Monaco Editor failed to load: {reason}"
+                            }
+                        },
+                    }
+                }
+
+                // Debug section
+                div {
+                    style: "margin-top: 10px; padding: 10px; background-color: #222; border-radius: 4px; font-size: 12px;",
+                    if let Some(command) = last_command() {
+                        p {
+                            style: "margin: 0 0 5px 0; color: #8fd18f;",
+                            "{command}"
+                        }
+                    }
+                    p {
+                        style: "margin: 0; color: #888;",
+                        "💡 Edits are pushed into the Rust signal as you type; the buttons drive the editor through its MonacoHandle"
+                    }
+                    p {
+                        style: "margin: 5px 0 0 0; color: #888;",
+                        "🔧 Use 'Print Stored Content' to see what's in the Rust signal"
+                    }
+                    p {
+                        style: "margin: 5px 0 0 0; color: #888;",
+                        "🚦 Type eval(, debugger, var or host.print( to see diagnostics from the Rust validator, with a quick fix for the last one"
+                    }
+                    p {
+                        style: "margin: 5px 0 0 0; color: #888;",
+                        "🧩 Type host. to complete host API calls from Rust; hover them or open their parentheses for docs and parameters"
+                    }
+                    p {
+                        style: "margin: 5px 0 0 0; color: #888;",
+                        "👻 Pause after host.fetchJson( for ghost-text arguments (Tab accepts); parameter names and the types of const x = host.load(…) show up as inlay hints"
+                    }
+                    p {
+                        style: "margin: 5px 0 0 0; color: #888;",
+                        "▶ Write function main() or function testSomething() to get Run and Test lenses handled in Rust"
+                    }
+                    p {
+                        style: "margin: 5px 0 0 0; color: #888;",
+                        "🔗 Call formatPrice() from the second editor, then use F12 or F2 on it to jump or rename across both"
+                    }
+                    if cfg!(feature = "tree-sitter") {
+                        p {
+                            style: "margin: 5px 0 0 0; color: #888;",
+                            "🌳 Highlighting in this editor comes from a tree-sitter parse; try the snippet-night theme to see calls and properties stand out"
+                        }
+                    }
+                }
+            }
+
+            div {
+                style: "width: 100%; margin-top: 20px;",
+                div {
+                    style: "margin-bottom: 10px;",
+                    "File name: "
+                    select {
+                        value: "{second_file}",
+                        onchange: move |event| second_file.set(event.value()),
+                        for file in SNIPPET_FILES {
+                            option { value: *file, "{file}" }
+                        }
+                    }
+                    span { style: "margin-left: 10px; color: #888;", "language: {second_language}" }
+                }
+                MonacoEditor {
+                    initial_value: second_code,
+                    language: second_language.clone(),
+                    languages: custom_languages.clone(),
+                    theme: theme.clone(),
+                    providers: second_providers,
+                    onchange: move |text: String| println!("Second editor changed ({} chars)", text.len()),
+                }
+            }
+
+            div {
+                style: "width: 100%; margin-top: 20px;",
+                div {
+                    style: "margin-bottom: 10px; display: flex; gap: 10px; align-items: center; flex-wrap: wrap;",
+                    "Review before publishing: "
+                    span { style: "color: #888;", "{unpublished_changes} change(s)" }
+                    button {
+                        disabled: unpublished_changes() == 0,
+                        onclick: move |_| {
+                            if let Some(review) = review() {
+                                spawn(async move {
+                                    if let Err(e) = review.previous_change().await {
+                                        println!("Previous change failed: {}", e);
+                                    }
+                                });
+                            }
+                        },
+                        style: "{BUTTON_STYLE} background-color: #6c757d;",
+                        "◀ Previous"
+                    }
+                    button {
+                        disabled: unpublished_changes() == 0,
+                        onclick: move |_| {
+                            if let Some(review) = review() {
+                                spawn(async move {
+                                    if let Err(e) = review.next_change().await {
+                                        println!("Next change failed: {}", e);
+                                    }
+                                });
+                            }
+                        },
+                        style: "{BUTTON_STYLE} background-color: #6c757d;",
+                        "Next ▶"
+                    }
+                    button {
+                        onclick: move |_| review_inline.toggle(),
+                        style: "{BUTTON_STYLE} background-color: #17a2b8;",
+                        if review_inline() { "Side by side" } else { "Inline" }
+                    }
+                    button {
+                        disabled: unpublished_changes() == 0,
+                        onclick: move |_| published.set(snippet()),
+                        style: "{BUTTON_STYLE} background-color: #28a745;",
+                        "Publish"
+                    }
+                }
+                // Edits on the right go straight into the first editor's snippet
+                MonacoDiffEditor {
+                    original: published(),
+                    modified: snippet(),
+                    editable: true,
+                    inline: review_inline(),
+                    theme: theme.clone(),
+                    onchange: move |text: String| snippet.set(text),
+                    ondiff: move |changes: Vec<LineChange>| unpublished_changes.set(changes.len()),
+                    onready: move |editor: MonacoDiffHandle| review.set(Some(editor)),
+                    onerror: move |error: MonacoError| println!("Review editor error: {}", error),
+                }
+            }
+
+            div {
+                style: "width: 100%; margin-top: 20px;",
+                div { style: "margin-bottom: 10px;", "Resolve conflicting edits: " }
+                // The accepted merge replaces the first editor's snippet
+                MergeEditor {
+                    base: MERGE_BASE,
+                    ours: MERGE_OURS,
+                    theirs: MERGE_THEIRS,
+                    theme: theme.clone(),
+                    onaccept: move |merged: String| {
+                        println!("Merge accepted ({} chars)", merged.len());
+                        snippet.set(merged);
+                    },
+                }
+            }
+
+            div {
+                style: "width: 100%; margin-top: 20px;",
+                div {
+                    style: "margin-bottom: 10px; display: flex; gap: 10px; align-items: center;",
+                    "Open snippets: "
+                    span { style: "color: #888;", "{open_tabs.read().len()} tab(s), showing {active_tab}" }
+                    button {
+                        onclick: move |_| {
+                            untitled += 1;
+                            let uri = format!("file:///snippets/untitled-{}.js", untitled());
+                            open_tabs.write().push(EditorTab::new(uri.clone(), ""));
+                            active_tab.set(uri);
+                        },
+                        style: "{BUTTON_STYLE} background-color: #6c757d;",
+                        "New tab"
+                    }
+                }
+                // Each tab keeps its cursor, scroll position and folds while another one is shown
+                EditorTabs {
+                    tabs: open_tabs,
+                    active: active_tab,
+                    languages: custom_languages,
+                    theme: theme.clone(),
+                }
+            }
+
+            div {
+                style: "width: 100%; margin-top: 20px;",
+                div {
+                    style: "margin-bottom: 10px; display: flex; gap: 10px; align-items: center;",
+                    "Order hook template: "
+                    span { style: "color: #888;", "the shaded header and footer are fixed" }
+                    button {
+                        onclick: move |_| template_locked.toggle(),
+                        style: "{BUTTON_STYLE} background-color: #6c757d;",
+                        if template_locked() { "Unlock" } else { "Lock all" }
+                    }
+                }
+                MonacoEditor {
+                    initial_value: ORDER_HOOK_TEMPLATE,
+                    read_only: template_locked(),
+                    protected_ranges: vec![Range::lines(1, 2), Range::lines(4, 4)],
+                    theme,
+                    onchange: move |text: String| println!("Order hook changed ({} chars)", text.len()),
+                }
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_columns_count_utf16_units() {
+        let diagnostics = SnippetRules.validate("const s = '😀'; eval(s);", "javascript");
+        assert_eq!(diagnostics.len(), 1);
+        let range = diagnostics[0].range;
+        assert_eq!((range.start_column, range.end_column), (17, 22));
+    }
+}
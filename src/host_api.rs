@@ -0,0 +1,419 @@
+//! The functions the host app exposes to snippets, and the editor support built on them.
+
+use crate::monaco::{
+    CodeAction, CodeActionProvider, CodeActionRequest, CompletionItem, CompletionItemKind, CompletionProvider,
+    CompletionRequest, Hover, HoverProvider, HoverRequest, InlayHint, InlayHintRequest, InlayHintsProvider,
+    InlineCompletion, InlineCompletionProvider, InlineCompletionRequest, ParameterInformation, Position, SignatureHelp,
+    SignatureHelpProvider, SignatureHelpRequest, SignatureInformation, TextEdit, WorkspaceEdit,
+};
+
+/// Marker code for calls to a [`DEPRECATED_FUNCTIONS`] entry.
+pub const DEPRECATED_CODE: &str = "deprecated-host-api";
+
+/// Host functions that still work but have a replacement, as `(old, new)`.
+pub const DEPRECATED_FUNCTIONS: &[(&str, &str)] = &[("print", "log"), ("save", "store")];
+
+pub struct HostFunction {
+    pub name: &'static str,
+    pub params: &'static [&'static str],
+    pub returns: &'static str,
+    pub doc: &'static str,
+}
+
+impl HostFunction {
+    pub fn signature(&self) -> String {
+        format!("host.{}({}): {}", self.name, self.params.join(", "), self.returns)
+    }
+
+    fn param_names(&self) -> impl Iterator<Item = &'static str> {
+        self.params.iter().map(|param| param.split(':').next().unwrap_or(param).trim())
+    }
+
+    /// Call snippet with one tab-stop per parameter.
+    fn snippet(&self) -> String {
+        let params: Vec<String> =
+            self.param_names().enumerate().map(|(i, name)| format!("${{{}:{}}}", i + 1, name)).collect();
+        format!("{}({})$0", self.name, params.join(", "))
+    }
+}
+
+pub fn host_function(name: &str) -> Option<&'static HostFunction> {
+    HOST_FUNCTIONS.iter().find(|function| function.name == name)
+}
+
+pub const HOST_FUNCTIONS: &[HostFunction] = &[
+    HostFunction {
+        name: "log",
+        params: &["message: string"],
+        returns: "void",
+        doc: "Writes `message` to the host console.",
+    },
+    HostFunction {
+        name: "fetchJson",
+        params: &["url: string", "timeoutMs: number"],
+        returns: "Promise<object>",
+        doc: "Fetches `url` and parses the body as JSON. Fails after `timeoutMs` milliseconds.",
+    },
+    HostFunction {
+        name: "store",
+        params: &["key: string", "value: any"],
+        returns: "void",
+        doc: "Saves `value` under `key` in the host's snippet storage.",
+    },
+    HostFunction {
+        name: "load",
+        params: &["key: string"],
+        returns: "any",
+        doc: "Reads the value saved under `key`, or `undefined`.",
+    },
+];
+
+/// Editor support for the `host` object.
+pub struct HostApi;
+
+impl CompletionProvider for HostApi {
+    fn trigger_characters(&self) -> Vec<String> {
+        vec![".".to_string()]
+    }
+
+    fn provide_completions(&self, source: &str, request: &CompletionRequest) -> Vec<CompletionItem> {
+        // Only members of `host` are offered, so look at what precedes the word
+        let line = source.lines().nth((request.position.line_number as usize).saturating_sub(1)).unwrap_or("");
+        let Some(object) = before_column(line, request.position.column).strip_suffix(request.word.as_str()) else {
+            return Vec::new();
+        };
+        if !object.ends_with("host.") {
+            return Vec::new();
+        }
+
+        HOST_FUNCTIONS
+            .iter()
+            .map(|function| {
+                CompletionItem::new(function.name, CompletionItemKind::Function)
+                    .with_snippet(function.snippet())
+                    .with_detail(function.signature())
+                    .with_documentation(function.doc)
+            })
+            .collect()
+    }
+}
+
+/// The part of `line` before `column`, which Monaco counts from 1 in UTF-16 code units.
+fn before_column(line: &str, column: u32) -> &str {
+    let limit = (column as usize).saturating_sub(1);
+    let mut units = 0;
+    for (offset, c) in line.char_indices() {
+        if units >= limit {
+            return &line[..offset];
+        }
+        units += c.len_utf16();
+    }
+    line
+}
+
+impl HoverProvider for HostApi {
+    fn provide_hover(&self, source: &str, request: &HoverRequest) -> Option<Hover> {
+        let word = request.word.as_deref()?;
+        if word == "host" {
+            let names: Vec<String> = HOST_FUNCTIONS.iter().map(|function| format!("`{}`", function.name)).collect();
+            return Some(Hover::new([
+                "**host**".to_string(),
+                format!("Functions provided by the host app: {}", names.join(", ")),
+            ]));
+        }
+
+        let line = source.lines().nth((request.position.line_number as usize).saturating_sub(1))?;
+        let function = host_function(word)?;
+        // Only the word right after `host.` is one of its functions
+        let before_word = before_column(line, request.position.column)
+            .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_' || c == '$');
+        if !before_word.ends_with("host.") {
+            return None;
+        }
+        Some(Hover::new([format!("```typescript\n{}\n```", function.signature()), function.doc.to_string()]))
+    }
+}
+
+impl SignatureHelpProvider for HostApi {
+    fn provide_signature_help(&self, source: &str, request: &SignatureHelpRequest) -> Option<SignatureHelp> {
+        let line = source.lines().nth((request.position.line_number as usize).saturating_sub(1))?;
+        let (callee, active_parameter) = open_call(before_column(line, request.position.column))?;
+        let function = host_function(callee.strip_prefix("host.")?)?;
+
+        let signature = SignatureInformation {
+            label: function.signature(),
+            documentation: Some(function.doc.to_string()),
+            parameters: function
+                .params
+                .iter()
+                .map(|param| ParameterInformation { label: param.to_string(), documentation: None })
+                .collect(),
+        };
+        Some(SignatureHelp { signatures: vec![signature], active_signature: 0, active_parameter })
+    }
+}
+
+/// The callee of the innermost call still open at the end of `prefix`, and how many
+/// of its arguments have been completed so far.
+fn open_call(prefix: &str) -> Option<(&str, u32)> {
+    // Byte offset of each open parenthesis, with the commas seen inside it
+    let mut open: Vec<(usize, u32)> = Vec::new();
+    let mut quote = None;
+    for (offset, c) in prefix.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(c),
+            (None, '(') => open.push((offset, 0)),
+            (None, ')') => {
+                open.pop();
+            }
+            (None, ',') => {
+                if let Some((_, commas)) = open.last_mut() {
+                    *commas += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let (paren, commas) = open.pop()?;
+    let before = prefix[..paren].trim_end();
+    let start = before
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '$'))
+        .map_or(0, |i| i + 1);
+    Some((&before[start..], commas))
+}
+
+impl CodeActionProvider for HostApi {
+    fn provide_code_actions(&self, source: &str, request: &CodeActionRequest) -> Vec<CodeAction> {
+        request
+            .markers
+            .iter()
+            .filter(|marker| marker.code.as_deref() == Some(DEPRECATED_CODE))
+            .filter_map(|marker| {
+                // The marker covers `host.<old>`
+                let line = source.lines().nth((marker.range.start_line_number as usize).saturating_sub(1))?;
+                let start = (marker.range.start_column as usize).saturating_sub(1);
+                let len = (marker.range.end_column as usize).saturating_sub(start + 1);
+                let covered: String = line.chars().skip(start).take(len).collect();
+                let (old, new) = DEPRECATED_FUNCTIONS.iter().find(|(old, _)| covered == format!("host.{old}"))?;
+                let edit = WorkspaceEdit { edits: vec![TextEdit::new(marker.range, format!("host.{new}"))] };
+                Some(
+                    CodeAction::quick_fix(format!("Replace host.{old} with host.{new}"), edit)
+                        .fixing(marker.clone())
+                        .preferred(),
+                )
+            })
+            .collect()
+    }
+}
+
+impl InlayHintsProvider for HostApi {
+    fn provide_inlay_hints(&self, source: &str, request: &InlayHintRequest) -> Vec<InlayHint> {
+        let mut hints = Vec::new();
+        let lines = request.range.start_line_number..=request.range.end_line_number;
+        for (line_number, line) in (1..).zip(source.lines()).filter(|(number, _)| lines.contains(number)) {
+            if let Some((offset, returns)) = binding_type(line) {
+                hints.push(InlayHint::type_hint(Position { line_number, column: column_at(line, offset) }, returns));
+            }
+            let mut rest = line;
+            while let Some(found) = rest.find("host.") {
+                let call = &rest[found + "host.".len()..];
+                let name_len = call.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(call.len());
+                rest = &call[name_len..];
+                let Some(function) = host_function(&call[..name_len]) else { continue };
+                if !rest.starts_with('(') {
+                    continue;
+                }
+                let open = line.len() - rest.len();
+                for (offset, name) in argument_offsets(line, open).into_iter().zip(function.param_names()) {
+                    // `host.store(key, value)` needs no hints
+                    let argument = &line[offset..];
+                    let argument_len =
+                        argument.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(argument.len());
+                    if &argument[..argument_len] != name {
+                        let position = Position { line_number, column: column_at(line, offset) };
+                        hints.push(InlayHint::parameter(position, name));
+                    }
+                }
+            }
+        }
+        hints
+    }
+}
+
+/// For `const name = host.fn(...)`, the byte offset just past `name` and what `fn`
+/// returns, unwrapped from its promise if the call is awaited.
+fn binding_type(line: &str) -> Option<(usize, &'static str)> {
+    let trimmed = line.trim_start();
+    let rest = ["const ", "let ", "var "].iter().find_map(|keyword| trimmed.strip_prefix(keyword))?.trim_start();
+    let name_len = rest.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$')).unwrap_or(rest.len());
+    let value = rest[name_len..].trim_start().strip_prefix('=')?.trim_start();
+    let (awaited, value) = match value.strip_prefix("await ") {
+        Some(value) => (true, value.trim_start()),
+        None => (false, value),
+    };
+    let call = value.strip_prefix("host.")?;
+    let call_len = call.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(call.len());
+    let function = host_function(&call[..call_len])?;
+    if name_len == 0 || !call[call_len..].starts_with('(') || function.returns == "void" {
+        return None;
+    }
+    let returns = match function.returns.strip_prefix("Promise<").and_then(|inner| inner.strip_suffix('>')) {
+        Some(resolved) if awaited => resolved,
+        _ => function.returns,
+    };
+    Some((line.len() - rest.len() + name_len, returns))
+}
+
+/// The Monaco column of the byte at `offset` in `line`; Monaco counts UTF-16 code units.
+fn column_at(line: &str, offset: usize) -> u32 {
+    line[..offset].encode_utf16().count() as u32 + 1
+}
+
+/// Where each argument of the call whose parenthesis is at byte `open` starts, as
+/// byte offsets; only arguments on the same line are found.
+fn argument_offsets(line: &str, open: usize) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut expecting = true;
+    for (index, c) in line[open + 1..].char_indices() {
+        if expecting && !c.is_whitespace() {
+            expecting = false;
+            if c != ')' {
+                offsets.push(open + 1 + index);
+            }
+        }
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') if depth == 0 => break,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => expecting = true,
+            _ => {}
+        }
+    }
+    offsets
+}
+
+impl InlineCompletionProvider for HostApi {
+    fn provide_inline_completions(&self, source: &str, request: &InlineCompletionRequest) -> Vec<InlineCompletion> {
+        // Fills in the arguments of a call that has just been opened, at the end of the line
+        let Some(line) = source.lines().nth((request.position.line_number as usize).saturating_sub(1)) else {
+            return Vec::new();
+        };
+        if line.encode_utf16().count() + 1 != request.position.column as usize {
+            return Vec::new();
+        }
+        let Some((callee, 0)) = open_call(line) else { return Vec::new() };
+        let Some(function) = callee.strip_prefix("host.").and_then(host_function) else { return Vec::new() };
+        if !line.trim_end().ends_with('(') {
+            return Vec::new();
+        }
+        let arguments: Vec<String> = function
+            .params
+            .iter()
+            .zip(function.param_names())
+            .map(|(param, name)| if param.ends_with(": string") { format!("\"{name}\"") } else { name.to_string() })
+            .collect();
+        vec![InlineCompletion::new(format!("{});", arguments.join(", ")))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monaco::Range;
+
+    /// Argument offsets of the first call on `line`.
+    fn offsets(line: &str) -> Vec<usize> {
+        argument_offsets(line, line.find('(').unwrap())
+    }
+
+    #[test]
+    fn completion_columns_count_utf16_units() {
+        // The cursor is right after the second `host.`
+        let source = "host.log(\"😀\"); host.x";
+        let column = source.encode_utf16().count() as u32;
+        let request = CompletionRequest {
+            position: Position { line_number: 1, column },
+            word: String::new(),
+            trigger_character: Some(".".into()),
+        };
+        assert_eq!(HostApi.provide_completions(source, &request).len(), HOST_FUNCTIONS.len());
+        assert_eq!(before_column("a😀b", 4), "a😀");
+        assert_eq!(before_column("a😀b", 9), "a😀b");
+    }
+
+    #[test]
+    fn signature_columns_count_utf16_units() {
+        // The cursor is inside the second argument, before the closing parenthesis
+        let source = "host.store(\"😀\", x)";
+        let request = SignatureHelpRequest {
+            position: Position { line_number: 1, column: source.encode_utf16().count() as u32 },
+            trigger_character: None,
+            is_retrigger: false,
+        };
+        let help = HostApi.provide_signature_help(source, &request).unwrap();
+        assert_eq!(help.active_parameter, 1);
+    }
+
+    #[test]
+    fn hovers_only_follow_host() {
+        let source = "host.log(1); console.log(2);";
+        let hover = |column| {
+            let request = HoverRequest { position: Position { line_number: 1, column }, word: Some("log".into()) };
+            HostApi.provide_hover(source, &request)
+        };
+        assert!(hover(7).is_some());
+        assert!(hover(23).is_none());
+    }
+
+    #[test]
+    fn open_calls() {
+        assert_eq!(open_call("host.fetchJson("), Some(("host.fetchJson", 0)));
+        // Commas inside nested calls and strings belong to them
+        assert_eq!(open_call("host.store(join(a, b), "), Some(("host.store", 1)));
+        assert_eq!(open_call("host.store(\"a, b\", "), Some(("host.store", 1)));
+        assert_eq!(open_call("host.log(\"a, b"), Some(("host.log", 0)));
+        assert_eq!(open_call("x = host.store(key, value"), Some(("host.store", 1)));
+        // Before the parenthesis, or after it closes, there is no open call
+        assert_eq!(open_call("host.log"), None);
+        assert_eq!(open_call("host.log(message); "), None);
+    }
+
+    #[test]
+    fn bindings_of_host_calls_get_their_type() {
+        assert_eq!(binding_type("const data = await host.fetchJson(url, 500);"), Some((10, "object")));
+        assert_eq!(binding_type("  let raw = host.fetchJson(url, 500);"), Some((9, "Promise<object>")));
+        assert_eq!(binding_type("const x = host.log(\"hi\");"), None);
+        assert_eq!(binding_type("const x = other.load(key);"), None);
+    }
+
+    #[test]
+    fn hint_columns_count_utf16_units() {
+        let source = "const data = await host.fetchJson(\"😀\", 500);";
+        let request = InlayHintRequest { range: Range::lines(1, 1) };
+        let hints = HostApi.provide_inlay_hints(source, &request);
+        let columns: Vec<(u32, &str)> = hints.iter().map(|hint| (hint.position.column, hint.label.as_str())).collect();
+        assert_eq!(columns, vec![(11, ": object"), (35, "url:"), (41, "timeoutMs:")]);
+
+        let request = InlineCompletionRequest { position: Position { line_number: 1, column: 18 }, explicit: false };
+        assert_eq!(HostApi.provide_inline_completions("/* 😀 */host.log(", &request).len(), 1);
+    }
+
+    #[test]
+    fn argument_starts() {
+        assert_eq!(offsets("host.store(key, value)"), vec![11, 16]);
+        assert_eq!(offsets("host.store(join(a, b), value)"), vec![11, 23]);
+        assert_eq!(offsets("host.store(\"a, b\", value)"), vec![11, 19]);
+        // An unclosed call has arguments up to the end of the line
+        assert_eq!(offsets("host.fetchJson(url,  "), vec![15]);
+        assert_eq!(offsets("host.fetchJson(url, 500"), vec![15, 20]);
+        assert_eq!(offsets("host.load()"), Vec::<usize>::new());
+    }
+}
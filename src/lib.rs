@@ -0,0 +1,4 @@
+//! Monaco editor components for Dioxus, and the bridge they drive Monaco through.
+
+pub mod components;
+pub mod monaco;
@@ -8,7 +8,11 @@ use std::process::exit;
 
 
 mod content;
-mod components;
+mod host_api;
+mod snippet_links;
+mod snippet_outline;
+
+use monaco_in_dioxus::{components, monaco};
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
 const MAIN_CSS: Asset = asset!("/assets/main.css");
@@ -117,15 +121,11 @@ fn main() {
                     color: white;
                 }}
                 
-                #monaco-editor-container {{
+                .monaco-editor-container {{
                     position: relative;
                     overflow: hidden;
                     border: 1px solid #444;
                 }}
-                
-                .monaco-editor, .monaco-editor-background, .monaco-editor .margin {{
-                    background-color: #1e1e1e !important;
-                }}
             </style>
             <script>
                 const script = document.createElement('script');
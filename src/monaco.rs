@@ -0,0 +1,37 @@
+pub(crate) mod bridge;
+pub mod commands;
+pub mod custom_language;
+pub mod diagnostics;
+pub mod error;
+pub mod handle;
+pub mod language;
+pub(crate) mod marshal;
+pub mod merge;
+pub mod options;
+pub mod protocol;
+pub mod providers;
+pub mod theme;
+pub mod types;
+
+pub use commands::Commands;
+pub use custom_language::{LanguageConfiguration, LanguageDefinition, MonarchLanguage};
+pub use diagnostics::{Diagnostic, Severity, SnippetValidator, Validator};
+pub use error::MonacoError;
+pub use handle::{MonacoDiffHandle, MonacoHandle};
+pub use language::{language_for_extension, language_for_path};
+pub use merge::{
+    conflict_regions, merge, resolve_conflict, ConflictMarkers, ConflictRegion, MergeHunk, MergeResult, Resolution,
+};
+pub use options::EditorOptions;
+pub use providers::{
+    CodeAction, CodeActionProvider, CodeActionRequest, CodeLens, CodeLensProvider, CompletionItem, CompletionItemKind,
+    CompletionProvider, CompletionRequest, DefinitionProvider, DefinitionRequest, DocumentSymbol, DocumentSymbolProvider,
+    Hover, HoverProvider, HoverRequest, InlayHint, InlayHintKind, InlayHintRequest, InlayHintsProvider, InlineCompletion,
+    InlineCompletionProvider, InlineCompletionRequest, LensCommand, ParameterInformation, Providers, RenameProvider,
+    RenameRequest, SemanticToken, SemanticTokensLegend, SemanticTokensProvider, SignatureHelp, SignatureHelpProvider,
+    SignatureHelpRequest, SignatureInformation, SymbolKind,
+};
+#[cfg(feature = "tree-sitter")]
+pub use providers::TreeSitterTokens;
+pub use theme::{BaseTheme, EditorTheme, MonacoTheme, TokenRule};
+pub use types::{LineChange, Location, OpenDocument, Position, Range, TextEdit, ViewState, WorkspaceEdit};
@@ -0,0 +1,171 @@
+//! Rust end of one editor instance's eval channel.
+
+use dioxus::prelude::{EventHandler, Readable, Signal, Writable};
+use dioxus_document as document;
+use dioxus_document::Eval;
+use futures::channel::{mpsc, oneshot};
+use futures::future::{select, Either};
+use futures::StreamExt;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::pin::pin;
+
+use super::error::MonacoError;
+use super::marshal::bind;
+use super::protocol::{Event, Message, MessageBody, Op, Request, PROTOCOL_VERSION};
+use crate::components::EditorStatus;
+
+/// The dispatcher, defined once per page no matter how many instances evaluate it.
+const BRIDGE_JS: &str = include_str!("../../assets/monaco_bridge.js");
+
+pub(crate) type Reply = oneshot::Sender<Result<Value, MonacoError>>;
+
+/// What the owning component or a [`MonacoHandle`](super::MonacoHandle) asks of the bridge task.
+pub(crate) enum BridgeCommand {
+    /// The container is in the DOM, so the bridge can be attached.
+    Mount,
+    /// Send `op` and route its outcome to `reply`, if anyone is waiting for it.
+    Send { op: Op, reply: Option<Reply> },
+}
+
+pub(crate) struct Bridge {
+    eval: Eval,
+    next_id: u64,
+    pending: HashMap<u64, Reply>,
+}
+
+impl Bridge {
+    /// Starts the long-lived script for the instance whose container has id `key`.
+    pub(crate) fn attach(key: &str) -> Result<Self, MonacoError> {
+        let js = format!(
+            "{}{}{}\nawait window.monacoBridge.run(key, dioxus);",
+            bind("PROTOCOL_VERSION", &PROTOCOL_VERSION)?,
+            bind("key", key)?,
+            BRIDGE_JS,
+        );
+        Ok(Self { eval: document::eval(&js), next_id: 0, pending: HashMap::new() })
+    }
+
+    pub(crate) fn send(&mut self, op: Op, reply: Option<Reply>) {
+        self.next_id += 1;
+        let id = self.next_id;
+        match self.eval.send(Request::new(id, op)) {
+            Ok(()) => {
+                if let Some(reply) = reply {
+                    self.pending.insert(id, reply);
+                }
+            }
+            Err(e) => {
+                if let Some(reply) = reply {
+                    let _ = reply.send(Err(e.into()));
+                }
+            }
+        }
+    }
+
+    /// Waits for the next event, routing any responses that arrive first to their waiters.
+    ///
+    /// Errors are per message except for [`MonacoError::EvalFailed`], which means the
+    /// channel is gone.
+    pub(crate) async fn next_event(&mut self) -> Result<Event, MonacoError> {
+        loop {
+            let raw = self.eval.recv::<Value>().await?;
+            match Message::parse(raw)?.body {
+                MessageBody::Response { id, outcome } => {
+                    if let Some(reply) = self.pending.remove(&id) {
+                        let _ = reply.send(outcome.into_result());
+                    }
+                }
+                MessageBody::Event { event } => return Ok(event),
+            }
+        }
+    }
+
+    /// Disposes the editor of instance `key`. Runs as its own script, since the
+    /// instance's channel goes away together with its component.
+    pub(crate) fn dispose(key: &str) {
+        let Ok(key) = bind("key", key) else { return };
+        let _ = document::eval(&format!(
+            "{key}if (window.monacoBridge) {{ window.monacoBridge.dispose(key); }}"
+        ));
+    }
+}
+
+/// Drives the bridge of instance `key` for as long as its component lives.
+///
+/// Waits for the container to be mounted, attaches, and lets `setup` queue the ops
+/// creating the instance. Commands are then sent as they come in, and events are
+/// handed to `on_event`, except for the lifecycle ones, which go to `status`,
+/// `onready` and `onerror`.
+pub(crate) async fn run(
+    key: &str,
+    commands: &mut mpsc::UnboundedReceiver<BridgeCommand>,
+    mut status: Signal<EditorStatus>,
+    onready: impl Fn(),
+    onerror: Option<EventHandler<MonacoError>>,
+    setup: impl FnOnce(&mut Bridge),
+    mut on_event: impl FnMut(&mut Bridge, Event),
+) {
+    let report = move |error: MonacoError| {
+        if let Some(handler) = onerror {
+            handler.call(error);
+        }
+    };
+
+    // Nothing to talk to until the container has been mounted
+    loop {
+        match commands.next().await {
+            Some(BridgeCommand::Mount) => break,
+            Some(BridgeCommand::Send { reply, .. }) => {
+                if let Some(reply) = reply {
+                    let _ = reply.send(Err(MonacoError::NotInitialized));
+                }
+            }
+            None => return,
+        }
+    }
+
+    let mut bridge = match Bridge::attach(key) {
+        Ok(bridge) => bridge,
+        Err(error) => {
+            status.set(EditorStatus::Failed(error.to_string()));
+            report(error);
+            return;
+        }
+    };
+    setup(&mut bridge);
+
+    loop {
+        let next = {
+            let event = pin!(bridge.next_event());
+            match select(event, commands.next()).await {
+                Either::Left((event, _)) => Either::Left(event),
+                Either::Right((command, _)) => Either::Right(command),
+            }
+        };
+
+        match next {
+            Either::Left(Ok(Event::Ready)) => {
+                status.set(EditorStatus::Ready);
+                onready();
+            }
+            Either::Left(Ok(Event::Error { message, stack })) => {
+                let error = MonacoError::JsException { message, stack };
+                status.set(EditorStatus::Failed(error.to_string()));
+                report(error);
+            }
+            Either::Left(Ok(event)) => on_event(&mut bridge, event),
+            Either::Left(Err(error @ MonacoError::EvalFailed(_))) => {
+                if *status.peek() == EditorStatus::Loading {
+                    status.set(EditorStatus::Failed(error.to_string()));
+                }
+                report(error);
+                break;
+            }
+            Either::Left(Err(error)) => report(error),
+            Either::Right(Some(BridgeCommand::Send { op, reply })) => bridge.send(op, reply),
+            Either::Right(Some(BridgeCommand::Mount)) => {}
+            Either::Right(None) => break,
+        }
+    }
+}
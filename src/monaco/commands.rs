@@ -0,0 +1,75 @@
+//! Editor commands handled in Rust, such as the ones behind code lenses.
+
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+type Handler = Rc<RefCell<dyn FnMut(&[Value])>>;
+
+/// Command handlers for a `MonacoEditor`, keyed by command id:
+///
+/// ```ignore
+/// Commands::new().on("snippet.run", move |args| println!("run {args:?}"))
+/// ```
+///
+/// Like [`Providers`](super::Providers), two values are equal only if they are
+/// clones of the same set.
+#[derive(Clone, Default)]
+pub struct Commands {
+    handlers: Rc<HashMap<String, Handler>>,
+}
+
+impl Commands {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `handler` with the command's arguments whenever `id` is executed.
+    pub fn on(mut self, id: impl Into<String>, handler: impl FnMut(&[Value]) + 'static) -> Self {
+        Rc::make_mut(&mut self.handlers).insert(id.into(), Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    /// Runs the handler for `id`, returning whether there was one.
+    pub(crate) fn run(&self, id: &str, args: &[Value]) -> bool {
+        match self.handlers.get(id) {
+            Some(handler) => {
+                (handler.borrow_mut())(args);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl PartialEq for Commands {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.handlers, &other.handlers)
+    }
+}
+
+impl std::fmt::Debug for Commands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn commands_run_their_handler() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let commands = Commands::new().on("run", move |args| log.borrow_mut().extend_from_slice(args));
+
+        assert!(commands.run("run", &[json!("main")]));
+        assert!(!commands.run("test", &[]));
+        assert_eq!(*seen.borrow(), vec![json!("main")]);
+        assert_eq!(commands, commands.clone());
+        assert_ne!(commands, Commands::new());
+    }
+}
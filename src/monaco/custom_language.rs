@@ -0,0 +1,218 @@
+//! Languages declared in Rust: a Monarch tokenizer plus a language configuration,
+//! registered with Monaco before the editor's model is created.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A language Monaco does not ship, e.g. the host app's own DSL.
+///
+/// Usually loaded from a file with [`load`](Self::load); the JSON and TOML forms
+/// use the same field names as the struct.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LanguageDefinition {
+    pub id: String,
+    /// Extensions including the dot, e.g. `.pipeline`.
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Display names; the first one shows up in Monaco's language picker.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub tokenizer: MonarchLanguage,
+    #[serde(default)]
+    pub configuration: LanguageConfiguration,
+}
+
+impl LanguageDefinition {
+    pub fn from_json(source: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(source)
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(source)
+    }
+
+    /// Reads a definition from a `.json` or `.toml` file.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&source).map_err(|e| invalid(e.to_string())),
+            _ => Self::from_json(&source).map_err(|e| invalid(e.to_string())),
+        }
+    }
+
+    /// Whether `path` has one of this language's extensions.
+    pub fn matches_path(&self, path: &str) -> bool {
+        let lowercase = path.to_ascii_lowercase();
+        self.extensions
+            .iter()
+            .any(|extension| lowercase.ends_with(&extension.to_ascii_lowercase()) && lowercase.len() > extension.len())
+    }
+}
+
+/// Monaco's `IMonarchLanguage`. Regexes are written as strings.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonarchLanguage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_postfix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_case: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub brackets: Vec<MonarchBracket>,
+    /// Rules per state; tokenizing starts in `root`.
+    pub tokenizer: BTreeMap<String, Vec<MonarchRule>>,
+    /// Everything else, typically word lists like `keywords` and shared regexes like
+    /// `symbols`, referred to from rules as `@keywords` and `@symbols`.
+    #[serde(flatten)]
+    pub attributes: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MonarchBracket {
+    pub open: String,
+    pub close: String,
+    pub token: String,
+}
+
+/// One tokenizer rule, in any of the shapes Monarch accepts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MonarchRule {
+    /// `{ "include": "@state" }`
+    Include { include: String },
+    /// `[regex, action, next]`
+    MatchNext(String, MonarchAction, String),
+    /// `[regex, action]`
+    Match(String, MonarchAction),
+    /// `{ "regex": ..., "action": ... }`
+    Object { regex: String, action: MonarchAction },
+}
+
+/// What a matched rule produces.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MonarchAction {
+    /// A token class, e.g. `keyword`.
+    Token(String),
+    /// One action per regex group.
+    Groups(Vec<MonarchAction>),
+    Full(MonarchActionObject),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonarchActionObject {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// `@push`, `@pop` or a state name, e.g. `@string`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    /// Picks the action by the matched text, e.g. `{"@keywords": "keyword", "@default": "identifier"}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cases: Option<BTreeMap<String, MonarchAction>>,
+    /// `@open` or `@close`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bracket: Option<String>,
+    /// Anything else Monarch supports, such as `switchTo` or `goBack`.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Monaco's `LanguageConfiguration`, without the parts that need live regexes
+/// except for `word_pattern`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageConfiguration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comments: Option<CommentRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub brackets: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_closing_pairs: Vec<AutoClosingPair>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub surrounding_pairs: Vec<AutoClosingPair>,
+    /// What counts as a word, as a regex string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_pattern: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_comment: Option<(String, String)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoClosingPair {
+    pub open: String,
+    pub close: String,
+    /// Scopes where the pair is not closed, e.g. `string` or `comment`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_in: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TOML: &str = r##"
+        id = "pipeline"
+        extensions = [".pipeline"]
+
+        [tokenizer]
+        defaultToken = "invalid"
+        keywords = ["step", "when"]
+
+        [tokenizer.tokenizer]
+        root = [
+            ['[a-z_]\w*', { cases = { "@keywords" = "keyword", "@default" = "identifier" } }],
+            ['"', "string", "@string"],
+            { include = "@whitespace" },
+        ]
+        string = [['[^"]+', "string"], ['"', "string", "@pop"]]
+        whitespace = [['\s+', "white"], ['#.*$', "comment"]]
+
+        [configuration]
+        comments = { lineComment = "#" }
+        brackets = [["{", "}"]]
+        autoClosingPairs = [{ open = '"', close = '"', notIn = ["string"] }]
+    "##;
+
+    #[test]
+    fn toml_definitions_serialize_as_monarch() {
+        let language = LanguageDefinition::from_toml(TOML).unwrap();
+        assert!(language.matches_path("jobs/nightly.PIPELINE"));
+        assert!(!language.matches_path(".pipeline"));
+
+        let monarch = serde_json::to_value(&language.tokenizer).unwrap();
+        assert_eq!(monarch["defaultToken"], json!("invalid"));
+        assert_eq!(monarch["keywords"], json!(["step", "when"]));
+        assert_eq!(
+            monarch["tokenizer"]["root"],
+            json!([
+                ["[a-z_]\\w*", { "cases": { "@default": "identifier", "@keywords": "keyword" } }],
+                ["\"", "string", "@string"],
+                { "include": "@whitespace" }
+            ])
+        );
+        assert_eq!(
+            serde_json::to_value(&language.configuration).unwrap(),
+            json!({
+                "comments": { "lineComment": "#" },
+                "brackets": [["{", "}"]],
+                "autoClosingPairs": [{ "open": "\"", "close": "\"", "notIn": ["string"] }]
+            })
+        );
+    }
+}
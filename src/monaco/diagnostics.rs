@@ -0,0 +1,152 @@
+//! Problems found by Rust-side validation, shown as Monaco markers.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::rc::Rc;
+
+use super::protocol::{ObjectRef, Op, Target};
+use super::types::Range;
+
+/// Marker owner used for everything a [`SnippetValidator`] reports.
+const MARKER_OWNER: &str = "rust-validator";
+
+/// Monaco's `MarkerSeverity`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Serialize for Severity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // The enum values Monaco uses, not a sequence
+        serializer.serialize_u8(match self {
+            Severity::Hint => 1,
+            Severity::Info => 2,
+            Severity::Warning => 4,
+            Severity::Error => 8,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Severity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(Severity::Hint),
+            2 => Ok(Severity::Info),
+            4 => Ok(Severity::Warning),
+            8 => Ok(Severity::Error),
+            other => Err(D::Error::custom(format!("unknown marker severity {other}"))),
+        }
+    }
+}
+
+/// One problem, serialized as Monaco's `IMarkerData`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    #[serde(flatten)]
+    pub range: Range,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(range: Range, severity: Severity, message: impl Into<String>) -> Self {
+        Self { range, severity, message: message.into(), code: None }
+    }
+
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+}
+
+/// Checks a snippet and reports what is wrong with it.
+///
+/// `MonacoEditor` calls this once typing pauses and replaces all markers it set
+/// before with the result, so returning nothing clears them.
+pub trait SnippetValidator {
+    fn validate(&self, source: &str, language: &str) -> Vec<Diagnostic>;
+}
+
+/// A shared [`SnippetValidator`], as taken by the `validator` prop. Two values
+/// are equal only if they are the same validator.
+#[derive(Clone)]
+pub struct Validator(Rc<dyn SnippetValidator>);
+
+impl Validator {
+    pub fn new(validator: impl SnippetValidator + 'static) -> Self {
+        Self(Rc::new(validator))
+    }
+
+    pub fn validate(&self, source: &str, language: &str) -> Vec<Diagnostic> {
+        self.0.validate(source, language)
+    }
+}
+
+impl<V: SnippetValidator + 'static> From<V> for Validator {
+    fn from(validator: V) -> Self {
+        Self::new(validator)
+    }
+}
+
+impl PartialEq for Validator {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::fmt::Debug for Validator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Validator")
+    }
+}
+
+/// The call replacing the validator's markers on the editor's model.
+pub(crate) fn set_markers_op(diagnostics: &[Diagnostic]) -> Op {
+    Op::call(
+        Target::MonacoEditor,
+        "setModelMarkers",
+        [ObjectRef::Model.arg(), json!(MARKER_OWNER), markers(diagnostics)],
+    )
+}
+
+fn markers(diagnostics: &[Diagnostic]) -> Value {
+    serde_json::to_value(diagnostics).unwrap_or_else(|_| json!([]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics_serialize_as_marker_data() {
+        let range = Range { start_line_number: 2, start_column: 1, end_line_number: 2, end_column: 5 };
+        let diagnostic = Diagnostic::new(range, Severity::Warning, "unused").with_code("W001");
+        assert_eq!(
+            markers(&[diagnostic]),
+            json!([{
+                "startLineNumber": 2, "startColumn": 1, "endLineNumber": 2, "endColumn": 5,
+                "severity": 4, "message": "unused", "code": "W001"
+            }])
+        );
+        assert_eq!(markers(&[]), json!([]));
+    }
+
+    #[test]
+    fn markers_read_back_as_diagnostics() {
+        let marker = json!({
+            "startLineNumber": 1, "startColumn": 2, "endLineNumber": 1, "endColumn": 3,
+            "severity": 8, "message": "bad", "owner": "rust-validator"
+        });
+        let diagnostic: Diagnostic = serde_json::from_value(marker).unwrap();
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.code, None);
+        assert!(serde_json::from_value::<Severity>(json!(3)).is_err());
+    }
+}
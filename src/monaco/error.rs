@@ -0,0 +1,55 @@
+use dioxus_document::EvalError;
+use std::fmt::Display;
+
+/// Everything that can go wrong on a call across the Rust/Monaco bridge.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MonacoError {
+    /// The editor has not been created yet (Monaco still loading, or not mounted).
+    NotInitialized,
+    /// The editor existed but has been disposed.
+    Disposed,
+    /// The script could not be run or its channel broke down.
+    EvalFailed(String),
+    /// The script ran but threw.
+    JsException { message: String, stack: Option<String> },
+    /// Monaco answered, but not with what the Rust side expected.
+    Deserialize(String),
+    /// The dispatcher speaks a different protocol version.
+    ProtocolMismatch { expected: u32, found: u32 },
+    /// A command ran in the editor, e.g. from a code lens, but no handler is set up for it.
+    UnhandledCommand(String),
+}
+
+impl Display for MonacoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonacoError::NotInitialized => write!(f, "Monaco editor is not initialized"),
+            MonacoError::Disposed => write!(f, "Monaco editor has been disposed"),
+            MonacoError::EvalFailed(e) => write!(f, "evaluating JavaScript failed: {e}"),
+            MonacoError::JsException { message, .. } => write!(f, "JavaScript exception: {message}"),
+            MonacoError::Deserialize(e) => write!(f, "unexpected reply from Monaco: {e}"),
+            MonacoError::ProtocolMismatch { expected, found } => {
+                write!(f, "bridge protocol mismatch: expected v{expected}, got v{found}")
+            }
+            MonacoError::UnhandledCommand(id) => write!(f, "no handler for command {id}"),
+        }
+    }
+}
+
+impl std::error::Error for MonacoError {}
+
+impl From<EvalError> for MonacoError {
+    fn from(e: EvalError) -> Self {
+        match e {
+            EvalError::Serialization(e) => MonacoError::Deserialize(e.to_string()),
+            EvalError::InvalidJs(e) | EvalError::Communication(e) => MonacoError::EvalFailed(e),
+            e => MonacoError::EvalFailed(e.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for MonacoError {
+    fn from(e: serde_json::Error) -> Self {
+        MonacoError::Deserialize(e.to_string())
+    }
+}
@@ -0,0 +1,238 @@
+use futures::channel::{mpsc::UnboundedSender, oneshot};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use super::bridge::BridgeCommand;
+use super::error::MonacoError;
+use super::protocol::{ObjectRef, Op, Target};
+use super::types::{LineChange, Position, Range, ViewState};
+
+/// Typed controller for one mounted `MonacoEditor`, handed out through its `onready` callback.
+///
+/// Every method runs against that instance only and resolves once Monaco has answered.
+/// Once the editor is unmounted, calls fail with [`MonacoError::Disposed`].
+#[derive(Clone)]
+pub struct MonacoHandle {
+    editor_id: String,
+    bridge: UnboundedSender<BridgeCommand>,
+}
+
+impl PartialEq for MonacoHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.editor_id == other.editor_id
+    }
+}
+
+impl std::fmt::Debug for MonacoHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MonacoHandle").field("editor_id", &self.editor_id).finish()
+    }
+}
+
+impl MonacoHandle {
+    pub(crate) fn new(editor_id: impl Into<String>, bridge: UnboundedSender<BridgeCommand>) -> Self {
+        Self { editor_id: editor_id.into(), bridge }
+    }
+
+    /// The DOM id of the editor container.
+    pub fn id(&self) -> &str {
+        &self.editor_id
+    }
+
+    pub async fn get_value(&self) -> Result<String, MonacoError> {
+        self.call(Target::Editor, "getValue", []).await
+    }
+
+    /// Replaces the whole content. The change is reported back like any other edit.
+    pub async fn set_value(&self, value: &str) -> Result<(), MonacoError> {
+        self.call(Target::Editor, "setValue", [json!(value)]).await
+    }
+
+    /// Inserts `text` at the cursor, replacing the selection if there is one.
+    pub async fn insert_at_cursor(&self, text: &str) -> Result<(), MonacoError> {
+        let edit = json!({ "range": ObjectRef::Selection.arg(), "text": text, "forceMoveMarkers": true });
+        self.call::<Value>(Target::Editor, "executeEdits", [json!("rust"), json!([edit])]).await?;
+        Ok(())
+    }
+
+    pub async fn get_selection(&self) -> Result<Range, MonacoError> {
+        self.call(Target::Editor, "getSelection", []).await
+    }
+
+    pub async fn set_selection(&self, range: Range) -> Result<(), MonacoError> {
+        self.call(Target::Editor, "setSelection", [json!(range)]).await
+    }
+
+    pub async fn get_position(&self) -> Result<Position, MonacoError> {
+        self.call(Target::Editor, "getPosition", []).await
+    }
+
+    /// Scrolls so that `line` (1-based) is centered in the viewport.
+    pub async fn reveal_line(&self, line: u32) -> Result<(), MonacoError> {
+        self.call(Target::Editor, "revealLineInCenter", [json!(line)]).await
+    }
+
+    /// Scrolls so that `range` is centered in the viewport.
+    pub async fn reveal_range(&self, range: Range) -> Result<(), MonacoError> {
+        self.call(Target::Editor, "revealRangeInCenter", [json!(range)]).await
+    }
+
+    pub async fn focus(&self) -> Result<(), MonacoError> {
+        self.call(Target::Editor, "focus", []).await
+    }
+
+    pub async fn undo(&self) -> Result<(), MonacoError> {
+        self.trigger_action("undo").await
+    }
+
+    pub async fn redo(&self) -> Result<(), MonacoError> {
+        self.trigger_action("redo").await
+    }
+
+    /// Runs a Monaco action or command by id, e.g. `editor.action.formatDocument`.
+    pub async fn trigger_action(&self, action_id: &str) -> Result<(), MonacoError> {
+        self.call(Target::Editor, "trigger", [json!("rust"), json!(action_id), Value::Null]).await
+    }
+
+    /// The cursor, scroll position and folding of the current model.
+    pub async fn save_view_state(&self) -> Result<Option<ViewState>, MonacoError> {
+        self.call(Target::Editor, "saveViewState", []).await
+    }
+
+    pub async fn restore_view_state(&self, view_state: &ViewState) -> Result<(), MonacoError> {
+        self.call(Target::Editor, "restoreViewState", [view_state.0.clone()]).await
+    }
+
+    /// Shows the model at `uri`, creating it from `value` and `language` if there is
+    /// none yet, and restores `view_state` on it. Resolves to the view state of the
+    /// model shown before, so it can be handed back when switching to that one again.
+    pub async fn open_model(
+        &self,
+        uri: &str,
+        value: &str,
+        language: &str,
+        view_state: Option<ViewState>,
+    ) -> Result<Option<ViewState>, MonacoError> {
+        let op = Op::OpenModel {
+            uri: uri.to_string(),
+            value: value.to_string(),
+            language: language.to_string(),
+            view_state,
+        };
+        Ok(serde_json::from_value(self.request(op).await?)?)
+    }
+
+    /// Disposes the model at `uri` once it is no longer needed. Models the editor is
+    /// showing, or that it did not create through [`open_model`](Self::open_model), are left alone.
+    pub async fn close_model(&self, uri: &str) -> Result<(), MonacoError> {
+        self.request(Op::CloseModel { uri: uri.to_string() }).await?;
+        Ok(())
+    }
+
+    /// Calls `method` on `target` and decodes the result.
+    pub(crate) async fn call<T: DeserializeOwned>(
+        &self,
+        target: Target,
+        method: &str,
+        args: impl IntoIterator<Item = Value>,
+    ) -> Result<T, MonacoError> {
+        let value = self.request(Op::call(target, method, args)).await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Sends `op` and waits for its outcome.
+    pub(crate) async fn request(&self, op: Op) -> Result<Value, MonacoError> {
+        let (reply, outcome) = oneshot::channel();
+        self.bridge
+            .unbounded_send(BridgeCommand::Send { op, reply: Some(reply) })
+            .map_err(|_| MonacoError::Disposed)?;
+        outcome.await.map_err(|_| MonacoError::Disposed)?
+    }
+
+    /// Sends `op` without waiting for it.
+    pub(crate) fn notify(&self, op: Op) {
+        let _ = self.bridge.unbounded_send(BridgeCommand::Send { op, reply: None });
+    }
+
+    pub(crate) fn mount(&self) {
+        let _ = self.bridge.unbounded_send(BridgeCommand::Mount);
+    }
+}
+
+/// Typed controller for one mounted `MonacoDiffEditor`, handed out through its `onready` callback.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonacoDiffHandle {
+    modified: MonacoHandle,
+}
+
+impl MonacoDiffHandle {
+    pub(crate) fn new(modified: MonacoHandle) -> Self {
+        Self { modified }
+    }
+
+    /// The modified side, which takes the same calls as a plain editor.
+    pub fn modified(&self) -> &MonacoHandle {
+        &self.modified
+    }
+
+    pub async fn get_original(&self) -> Result<String, MonacoError> {
+        self.modified.call(Target::OriginalModel, "getValue", []).await
+    }
+
+    pub async fn get_modified(&self) -> Result<String, MonacoError> {
+        self.modified.get_value().await
+    }
+
+    /// Moves the cursor to the next change and reveals it, wrapping around at the end.
+    pub async fn next_change(&self) -> Result<(), MonacoError> {
+        self.modified.call(Target::DiffEditor, "goToDiff", [json!("next")]).await
+    }
+
+    /// Moves the cursor to the previous change and reveals it, wrapping around at the start.
+    pub async fn previous_change(&self) -> Result<(), MonacoError> {
+        self.modified.call(Target::DiffEditor, "goToDiff", [json!("previous")]).await
+    }
+
+    /// The changes as of the last diff computation; empty while it is still running.
+    pub async fn line_changes(&self) -> Result<Vec<LineChange>, MonacoError> {
+        let changes: Option<Vec<LineChange>> = self.modified.call(Target::DiffEditor, "getLineChanges", []).await?;
+        Ok(changes.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc;
+    use futures::StreamExt;
+
+    // The bridge lifts protected ranges while a call runs, so edits from the handle must be calls
+    #[test]
+    fn edits_go_out_as_editor_calls() {
+        let (bridge, mut commands) = mpsc::unbounded();
+        let handle = MonacoHandle::new("editor", bridge);
+        let answer = async {
+            let mut sent = Vec::new();
+            while let Some(BridgeCommand::Send { op, reply }) = commands.next().await {
+                sent.push(serde_json::to_value(&op).unwrap());
+                reply.unwrap().send(Ok(Value::Null)).unwrap();
+                if sent.len() == 2 {
+                    break;
+                }
+            }
+            sent
+        };
+        let edits = async {
+            handle.set_value("fixed").await.unwrap();
+            handle.insert_at_cursor("x").await.unwrap();
+        };
+        let (sent, ()) = futures::executor::block_on(futures::future::join(answer, edits));
+
+        assert_eq!(sent[0]["op"], "call");
+        assert_eq!(sent[0]["target"], "editor");
+        assert_eq!(sent[0]["method"], "setValue");
+        assert_eq!(sent[1]["op"], "call");
+        assert_eq!(sent[1]["target"], "editor");
+        assert_eq!(sent[1]["method"], "executeEdits");
+    }
+}
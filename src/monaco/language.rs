@@ -0,0 +1,162 @@
+//! Monaco language ids, and picking one from a snippet's file name.
+
+/// The id Monaco uses for text without highlighting.
+pub const PLAIN_TEXT: &str = "plaintext";
+
+/// Every language bundled under `assets/min/vs`, with the extensions Monaco registers for it.
+/// Where two languages claim an extension, the first one listed wins.
+const LANGUAGE_EXTENSIONS: &[(&str, &[&str])] = &[
+    ("abap", &[".abap"]),
+    ("apex", &[".cls"]),
+    ("azcli", &[".azcli"]),
+    ("bat", &[".bat", ".cmd"]),
+    ("bicep", &[".bicep"]),
+    ("cameligo", &[".mligo"]),
+    ("clojure", &[".clj", ".cljs", ".cljc", ".edn"]),
+    ("coffeescript", &[".coffee"]),
+    ("c", &[".c", ".h"]),
+    ("cpp", &[".cpp", ".cc", ".cxx", ".hpp", ".hh", ".hxx"]),
+    ("csharp", &[".cs", ".csx", ".cake"]),
+    ("csp", &[".csp"]),
+    ("css", &[".css"]),
+    ("cypher", &[".cypher", ".cyp"]),
+    ("dart", &[".dart"]),
+    ("dockerfile", &[".dockerfile"]),
+    ("ecl", &[".ecl"]),
+    ("elixir", &[".ex", ".exs"]),
+    ("flow9", &[".flow"]),
+    ("fsharp", &[".fs", ".fsi", ".ml", ".mli", ".fsx", ".fsscript"]),
+    ("freemarker2", &[".ftl", ".ftlh", ".ftlx"]),
+    ("go", &[".go"]),
+    ("graphql", &[".graphql", ".gql"]),
+    ("handlebars", &[".handlebars", ".hbs"]),
+    ("hcl", &[".tf", ".tfvars", ".hcl"]),
+    ("html", &[".html", ".htm", ".shtml", ".xhtml", ".mdoc", ".jsp", ".asp", ".aspx", ".jshtm"]),
+    ("ini", &[".ini", ".properties", ".gitconfig"]),
+    ("java", &[".java", ".jav"]),
+    ("javascript", &[".js", ".es6", ".jsx", ".mjs", ".cjs"]),
+    ("julia", &[".jl"]),
+    ("kotlin", &[".kt", ".kts"]),
+    ("less", &[".less"]),
+    ("lexon", &[".lex"]),
+    ("lua", &[".lua"]),
+    ("liquid", &[".liquid", ".html.liquid"]),
+    ("m3", &[".m3", ".i3", ".mg", ".ig"]),
+    ("markdown", &[".md", ".markdown", ".mdown", ".mkdn", ".mkd", ".mdwn", ".mdtxt", ".mdtext"]),
+    ("mdx", &[".mdx"]),
+    ("mips", &[".s"]),
+    ("msdax", &[".dax", ".msdax"]),
+    ("objective-c", &[".m"]),
+    ("pascal", &[".pas", ".p", ".pp"]),
+    ("pascaligo", &[".ligo"]),
+    ("perl", &[".pl", ".pm"]),
+    ("php", &[".php", ".php4", ".php5", ".phtml", ".ctp"]),
+    ("pla", &[".pla"]),
+    ("postiats", &[".dats", ".sats", ".hats"]),
+    ("powerquery", &[".pq", ".pqm"]),
+    ("powershell", &[".ps1", ".psm1", ".psd1"]),
+    ("proto", &[".proto"]),
+    ("pug", &[".jade", ".pug"]),
+    ("python", &[".py", ".rpy", ".pyw", ".cpy", ".gyp", ".gypi"]),
+    ("qsharp", &[".qs"]),
+    ("r", &[".r", ".rhistory", ".rmd", ".rprofile", ".rt"]),
+    ("razor", &[".cshtml"]),
+    ("redis", &[".redis"]),
+    ("restructuredtext", &[".rst"]),
+    ("ruby", &[".rb", ".rbx", ".rjs", ".gemspec"]),
+    ("rust", &[".rs", ".rlib"]),
+    ("sb", &[".sb"]),
+    ("scala", &[".scala", ".sc", ".sbt"]),
+    ("scheme", &[".scm", ".ss", ".sch", ".rkt"]),
+    ("scss", &[".scss"]),
+    ("shell", &[".sh", ".bash"]),
+    ("sol", &[".sol"]),
+    ("aes", &[".aes"]),
+    ("sparql", &[".rq"]),
+    ("sql", &[".sql"]),
+    ("st", &[".st", ".iecst", ".iecplc", ".lc3lib", ".tcpou", ".tcdut", ".tcgvl", ".tcio"]),
+    ("systemverilog", &[".sv", ".svh"]),
+    ("verilog", &[".v", ".vh"]),
+    ("tcl", &[".tcl"]),
+    ("twig", &[".twig"]),
+    ("typescript", &[".ts", ".tsx", ".cts", ".mts"]),
+    ("typespec", &[".tsp"]),
+    ("vb", &[".vb"]),
+    ("wgsl", &[".wgsl"]),
+    (
+        "xml",
+        &[
+            ".xml", ".xsd", ".dtd", ".ascx", ".csproj", ".config", ".props", ".targets",
+            ".wxi", ".wxl", ".wxs", ".xaml", ".svg", ".svgz", ".opf", ".xslt",
+            ".xsl",
+        ],
+    ),
+    ("yaml", &[".yaml", ".yml"]),
+    ("json", &[".json", ".bowerrc", ".jshintrc", ".jscsrc", ".eslintrc", ".babelrc", ".har"]),
+];
+
+/// Exact file names Monaco recognizes regardless of extension.
+const LANGUAGE_FILENAMES: &[(&str, &str)] = &[
+    ("Dockerfile", "dockerfile"),
+    ("config", "ini"),
+    (".gitattributes", "ini"),
+    (".gitconfig", "ini"),
+    (".editorconfig", "ini"),
+    ("jakefile", "javascript"),
+    ("rakefile", "ruby"),
+    ("Gemfile", "ruby"),
+];
+
+/// Maps an extension such as `"py"` or `".py"` to a Monaco language id.
+pub fn language_for_extension(extension: &str) -> Option<&'static str> {
+    let extension = extension.trim_start_matches('.');
+    LANGUAGE_EXTENSIONS.iter().find_map(|(id, extensions)| {
+        extensions
+            .iter()
+            .any(|candidate| candidate[1..].eq_ignore_ascii_case(extension))
+            .then_some(*id)
+    })
+}
+
+/// Maps a file name or path such as `"scripts/cleanup.lua"` to a Monaco language id.
+///
+/// Exact file names like `Dockerfile` are checked first, then the longest matching
+/// extension, so `page.html.liquid` is Liquid rather than HTML.
+pub fn language_for_path(path: &str) -> Option<&'static str> {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    if let Some((_, id)) = LANGUAGE_FILENAMES.iter().find(|(file, _)| file.eq_ignore_ascii_case(name)) {
+        return Some(id);
+    }
+
+    let lowercase = name.to_ascii_lowercase();
+    LANGUAGE_EXTENSIONS
+        .iter()
+        .flat_map(|(id, extensions)| extensions.iter().map(move |extension| (*id, *extension)))
+        .filter(|(_, extension)| lowercase.ends_with(&extension.to_ascii_lowercase()) && lowercase.len() > extension.len())
+        .max_by_key(|(_, extension)| extension.len())
+        .map(|(id, _)| id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensions_with_or_without_dot() {
+        assert_eq!(language_for_extension("sql"), Some("sql"));
+        assert_eq!(language_for_extension(".PY"), Some("python"));
+        assert_eq!(language_for_extension("yml"), Some("yaml"));
+        assert_eq!(language_for_extension("nope"), None);
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(language_for_path("snippets/cleanup.lua"), Some("lua"));
+        assert_eq!(language_for_path(r"C:\snippets\Query.SQL"), Some("sql"));
+        assert_eq!(language_for_path("build/Dockerfile"), Some("dockerfile"));
+        assert_eq!(language_for_path("page.html.liquid"), Some("liquid"));
+        assert_eq!(language_for_path("notes"), None);
+        // A bare extension is a hidden file name, not a match
+        assert_eq!(language_for_path(".lua"), None);
+    }
+}
@@ -0,0 +1,68 @@
+//! Turning Rust values into JavaScript source.
+//!
+//! Every value that ends up inside a bridge script goes through here, so arbitrary
+//! snippet content can never break out of its literal or inject code.
+
+use serde::Serialize;
+
+use super::error::MonacoError;
+
+/// Serializes `value` as a JavaScript expression that evaluates to the same value.
+pub(crate) fn to_js<T: Serialize + ?Sized>(value: &T) -> Result<String, MonacoError> {
+    let json = serde_json::to_string(value)?;
+    // JSON is a JavaScript subset except for these two, which pre-ES2019 engines
+    // treat as line terminators inside string literals
+    Ok(json.replace('\u{2028}', "\\u2028").replace('\u{2029}', "\\u2029"))
+}
+
+/// A `const name = <value>;` line to put in front of a script body.
+pub(crate) fn bind<T: Serialize + ?Sized>(name: &str, value: &T) -> Result<String, MonacoError> {
+    Ok(format!("const {name} = {};\n", to_js(value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOSTILE: &[&str] = &[
+        "",
+        r#"console.log("quoted");"#,
+        r#"");alert(1);(""#,
+        "'); window.close(); ('",
+        r"C:\path\to\file \n is not a newline \\",
+        "line one\nline two\r\nline three\ttabbed",
+        "`${window.location}` template",
+        "</script><script>alert(1)</script>",
+        "separators: \u{2028} and \u{2029}",
+        "control: \u{0000}\u{0007}\u{001b}\u{007f}",
+        "unicode: héllo ✓ 🦀 \u{FEFF}",
+        "// trailing comment\n/* unterminated",
+    ];
+
+    #[test]
+    fn hostile_strings_round_trip() {
+        for input in HOSTILE {
+            let literal = to_js(*input).unwrap();
+            let decoded: String = serde_json::from_str(&literal).unwrap();
+            assert_eq!(decoded.as_bytes(), input.as_bytes(), "literal: {literal}");
+        }
+    }
+
+    #[test]
+    fn literals_stay_on_one_line() {
+        for input in HOSTILE {
+            let literal = to_js(*input).unwrap();
+            assert!(
+                !literal.contains(['\n', '\r', '\u{2028}', '\u{2029}']),
+                "raw line terminator in {literal:?}"
+            );
+            assert!(literal.starts_with('"') && literal.ends_with('"'));
+        }
+    }
+
+    #[test]
+    fn bind_declares_a_single_constant() {
+        let line = bind("args", &[r#"a"b"#, "c\nd"]).unwrap();
+        assert_eq!(line, "const args = [\"a\\\"b\",\"c\\nd\"];\n");
+    }
+}
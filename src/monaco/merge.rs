@@ -0,0 +1,332 @@
+//! Line-based three-way merge, for reconciling two edits of the same snippet.
+//!
+//! Conflicts that cannot be merged are written into the result with git's diff3
+//! markers, so the result text alone says what is still unresolved and can be
+//! edited by hand or resolved hunk by hunk with [`resolve_conflict`].
+
+use super::diagnostics::{Diagnostic, Severity, SnippetValidator};
+use super::types::Range;
+
+const OURS_MARKER: &str = "<<<<<<< ours";
+const BASE_MARKER: &str = "||||||| base";
+const SEPARATOR: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>> theirs";
+
+/// A stretch of the merge: lines that merged cleanly, or a conflict between the
+/// two edits of the same base lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeHunk {
+    Clean(Vec<String>),
+    Conflict { base: Vec<String>, ours: Vec<String>, theirs: Vec<String> },
+}
+
+/// The outcome of [`merge`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeResult {
+    pub hunks: Vec<MergeHunk>,
+}
+
+impl MergeResult {
+    pub fn conflicts(&self) -> usize {
+        self.hunks.iter().filter(|hunk| matches!(hunk, MergeHunk::Conflict { .. })).count()
+    }
+
+    /// The merged text, with conflict markers around every conflict.
+    pub fn text(&self) -> String {
+        let mut lines: Vec<&str> = Vec::new();
+        for hunk in &self.hunks {
+            match hunk {
+                MergeHunk::Clean(clean) => lines.extend(clean.iter().map(String::as_str)),
+                MergeHunk::Conflict { base, ours, theirs } => {
+                    lines.push(OURS_MARKER);
+                    lines.extend(ours.iter().map(String::as_str));
+                    lines.push(BASE_MARKER);
+                    lines.extend(base.iter().map(String::as_str));
+                    lines.push(SEPARATOR);
+                    lines.extend(theirs.iter().map(String::as_str));
+                    lines.push(THEIRS_MARKER);
+                }
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// Merges the changes `ours` and `theirs` made to `base`.
+///
+/// A change made on one side only, or identically on both, is taken as is; base
+/// lines changed differently on the two sides become a conflict. Diffing is
+/// quadratic in the number of lines, which is fine for snippets.
+pub fn merge(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let base: Vec<&str> = base.split('\n').collect();
+    let ours: Vec<&str> = ours.split('\n').collect();
+    let theirs: Vec<&str> = theirs.split('\n').collect();
+    let in_ours = matching_lines(&base, &ours);
+    let in_theirs = matching_lines(&base, &theirs);
+
+    let mut hunks = Vec::new();
+    let mut clean: Vec<String> = Vec::new();
+    let (mut b, mut o, mut t) = (0, 0, 0);
+    loop {
+        // Base lines both sides kept where they were
+        while b < base.len() && in_ours[b] == Some(o) && in_theirs[b] == Some(t) {
+            clean.push(base[b].to_string());
+            b += 1;
+            o += 1;
+            t += 1;
+        }
+        if b == base.len() && o == ours.len() && t == theirs.len() {
+            break;
+        }
+
+        // Up to the next base line both sides kept, something changed
+        let (next_b, next_o, next_t) = (b..base.len())
+            .find_map(|line| Some((line, in_ours[line]?, in_theirs[line]?)))
+            .unwrap_or((base.len(), ours.len(), theirs.len()));
+        let (base_part, ours_part, theirs_part) = (&base[b..next_b], &ours[o..next_o], &theirs[t..next_t]);
+        if ours_part == base_part || ours_part == theirs_part {
+            clean.extend(theirs_part.iter().map(|line| line.to_string()));
+        } else if theirs_part == base_part {
+            clean.extend(ours_part.iter().map(|line| line.to_string()));
+        } else {
+            if !clean.is_empty() {
+                hunks.push(MergeHunk::Clean(std::mem::take(&mut clean)));
+            }
+            let owned = |part: &[&str]| part.iter().map(|line| line.to_string()).collect();
+            hunks.push(MergeHunk::Conflict { base: owned(base_part), ours: owned(ours_part), theirs: owned(theirs_part) });
+        }
+        (b, o, t) = (next_b, next_o, next_t);
+    }
+    if !clean.is_empty() {
+        hunks.push(MergeHunk::Clean(clean));
+    }
+    MergeResult { hunks }
+}
+
+/// For every line of `base`, the index of the line it is kept as in `other`, from a
+/// longest common subsequence of the two.
+fn matching_lines(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    // lengths[i][j]: longest common subsequence of base[i..] and other[j..]
+    let mut lengths = vec![vec![0u32; other.len() + 1]; base.len() + 1];
+    for i in (0..base.len()).rev() {
+        for j in (0..other.len()).rev() {
+            lengths[i][j] = if base[i] == other[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![None; base.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < base.len() && j < other.len() {
+        if base[i] == other[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+/// A conflict still marked in a result text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConflictRegion {
+    /// 1-based lines of the opening and closing markers.
+    pub start_line_number: u32,
+    pub end_line_number: u32,
+    pub ours: Vec<String>,
+    pub base: Vec<String>,
+    pub theirs: Vec<String>,
+}
+
+impl ConflictRegion {
+    /// The whole region, markers included.
+    pub fn range(&self, text: &str) -> Range {
+        let end_line = text.split('\n').nth(self.end_line_number as usize - 1).unwrap_or("");
+        Range {
+            start_line_number: self.start_line_number,
+            start_column: 1,
+            end_line_number: self.end_line_number,
+            end_column: end_line.chars().count() as u32 + 1,
+        }
+    }
+}
+
+/// How to settle a conflict.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    /// Our lines followed by theirs.
+    Both,
+    /// Neither change.
+    Base,
+}
+
+/// The conflicts marked in `text`, in order. Incomplete marker blocks, e.g. after
+/// a hand edit removed the closing marker, are not counted as conflicts.
+pub fn conflict_regions(text: &str) -> Vec<ConflictRegion> {
+    let mut regions = Vec::new();
+    // The opening marker's line, then those of the base marker and the separator once seen
+    let mut open: Option<(usize, Option<usize>, Option<usize>)> = None;
+    let lines: Vec<&str> = text.split('\n').collect();
+    for (index, line) in lines.iter().enumerate() {
+        match open.as_mut() {
+            _ if line.starts_with("<<<<<<<") => open = Some((index, None, None)),
+            Some((_, base @ None, None)) if line.starts_with("|||||||") => *base = Some(index),
+            Some((_, _, separator @ None)) if line.starts_with(SEPARATOR) => *separator = Some(index),
+            Some((start, base, Some(separator))) if line.starts_with(">>>>>>>") => {
+                let owned = |part: &[&str]| part.iter().map(|line| line.to_string()).collect();
+                let ours_end = base.unwrap_or(*separator);
+                let base_lines = base.map(|base| owned(&lines[base + 1..*separator])).unwrap_or_default();
+                regions.push(ConflictRegion {
+                    start_line_number: *start as u32 + 1,
+                    end_line_number: index as u32 + 1,
+                    ours: owned(&lines[*start + 1..ours_end]),
+                    base: base_lines,
+                    theirs: owned(&lines[*separator + 1..index]),
+                });
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    regions
+}
+
+/// `text` with the `index`th of its [`conflict_regions`] replaced as `resolution`
+/// says. Returns `text` unchanged if there is no such conflict.
+pub fn resolve_conflict(text: &str, index: usize, resolution: Resolution) -> String {
+    let Some(region) = conflict_regions(text).into_iter().nth(index) else { return text.to_string() };
+    let replacement = match resolution {
+        Resolution::Ours => region.ours,
+        Resolution::Theirs => region.theirs,
+        Resolution::Both => region.ours.into_iter().chain(region.theirs).collect(),
+        Resolution::Base => region.base,
+    };
+
+    let lines: Vec<&str> = text.split('\n').collect();
+    let before = &lines[..region.start_line_number as usize - 1];
+    let after = &lines[region.end_line_number as usize..];
+    let mut result: Vec<&str> = before.to_vec();
+    result.extend(replacement.iter().map(String::as_str));
+    result.extend_from_slice(after);
+    result.join("\n")
+}
+
+/// Reports every conflict still marked in a result as an error.
+pub struct ConflictMarkers;
+
+impl SnippetValidator for ConflictMarkers {
+    fn validate(&self, source: &str, _language: &str) -> Vec<Diagnostic> {
+        conflict_regions(source)
+            .iter()
+            .map(|region| Diagnostic::new(region.range(source), Severity::Error, "Unresolved merge conflict"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "let total = 0;\nfor (const item of items) {\n  total += item.price;\n}\nlog(total);";
+
+    #[test]
+    fn changes_on_one_side_or_both_alike_merge_cleanly() {
+        let ours = BASE.replace("let total", "var total").replace("log(total)", "host.log(total)");
+        let theirs = BASE.replace("item.price", "item.price * item.count").replace("log(total)", "host.log(total)");
+        let merged = merge(BASE, &ours, &theirs);
+        assert_eq!(merged.conflicts(), 0);
+        assert_eq!(
+            merged.text(),
+            "var total = 0;\nfor (const item of items) {\n  total += item.price * item.count;\n}\nhost.log(total);"
+        );
+    }
+
+    #[test]
+    fn conflicts_are_marked_and_resolved_hunk_by_hunk() {
+        let ours = BASE.replace("item.price", "item.cost");
+        let theirs = BASE.replace("item.price", "item.amount").replace("let total", "const total");
+        let text = merge(BASE, &ours, &theirs).text();
+        assert_eq!(
+            text,
+            "const total = 0;\nfor (const item of items) {\n<<<<<<< ours\n  total += item.cost;\n||||||| base\n  \
+             total += item.price;\n=======\n  total += item.amount;\n>>>>>>> theirs\n}\nlog(total);"
+        );
+
+        let regions = conflict_regions(&text);
+        assert_eq!(regions.len(), 1);
+        assert_eq!((regions[0].start_line_number, regions[0].end_line_number), (3, 9));
+        assert_eq!(regions[0].base, vec!["  total += item.price;"]);
+        assert_eq!(ConflictMarkers.validate(&text, "javascript")[0].range.end_column, 15);
+
+        let resolved = resolve_conflict(&text, 0, Resolution::Both);
+        assert!(conflict_regions(&resolved).is_empty());
+        assert_eq!(
+            resolved,
+            "const total = 0;\nfor (const item of items) {\n  total += item.cost;\n  total += item.amount;\n}\nlog(total);"
+        );
+        assert_eq!(resolve_conflict(&resolved, 0, Resolution::Ours), resolved);
+    }
+
+    #[test]
+    fn insertions_at_the_same_point_merge_when_identical() {
+        let merged = merge("a\nb", "a\nx\nb", "a\nx\nb");
+        assert_eq!(merged.hunks, vec![MergeHunk::Clean(vec!["a".into(), "x".into(), "b".into()])]);
+
+        let merged = merge("a\nb", "a\nx\nb", "a\ny\nb");
+        let conflict = MergeHunk::Conflict { base: vec![], ours: vec!["x".into()], theirs: vec!["y".into()] };
+        assert_eq!(merged.hunks[1], conflict);
+        assert_eq!(merged.text(), "a\n<<<<<<< ours\nx\n||||||| base\n=======\ny\n>>>>>>> theirs\nb");
+    }
+
+    #[test]
+    fn deleting_what_the_other_side_edited_conflicts() {
+        let merged = merge("a\nb\nc", "a\nc", "a\nB\nc");
+        let conflict = MergeHunk::Conflict { base: vec!["b".into()], ours: vec![], theirs: vec!["B".into()] };
+        assert_eq!(merged.hunks[1], conflict);
+        assert_eq!(merged.conflicts(), 1);
+        assert_eq!(resolve_conflict(&merged.text(), 0, Resolution::Ours), "a\nc");
+    }
+
+    #[test]
+    fn an_empty_base_is_one_empty_line() {
+        assert_eq!(merge("", "a", "").text(), "a");
+        assert_eq!(merge("", "a", "a").text(), "a");
+        let text = merge("", "a", "b").text();
+        assert_eq!(text, "<<<<<<< ours\na\n||||||| base\n\n=======\nb\n>>>>>>> theirs");
+        assert_eq!(resolve_conflict(&text, 0, Resolution::Base), "");
+    }
+
+    #[test]
+    fn identical_changes_on_both_sides_are_taken_once() {
+        let changed = BASE.replace("item.price", "item.cost").replace("let total", "var total");
+        let merged = merge(BASE, &changed, &changed);
+        assert_eq!(merged.conflicts(), 0);
+        assert_eq!(merged.text(), changed);
+    }
+
+    #[test]
+    fn existing_markers_are_plain_text_to_the_merge() {
+        let marked = merge("a", "x", "y").text();
+        // Kept as is when only one side has them
+        assert_eq!(merge("a", &marked, "a").text(), marked);
+        assert_eq!(conflict_regions(&marked).len(), 1);
+
+        // Nested in a new conflict, only the inner region is found; settling it leaves the outer one whole
+        let nested = merge("a", &marked, "z").text();
+        let regions = conflict_regions(&nested);
+        assert_eq!(regions.len(), 1);
+        assert_eq!((regions[0].start_line_number, regions[0].end_line_number), (2, 8));
+        let settled = resolve_conflict(&nested, 0, Resolution::Ours);
+        assert_eq!(settled, "<<<<<<< ours\nx\n||||||| base\na\n=======\nz\n>>>>>>> theirs");
+        assert_eq!(conflict_regions(&settled)[0].theirs, vec!["z"]);
+    }
+}
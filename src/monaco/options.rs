@@ -0,0 +1,150 @@
+//! Typed subset of Monaco's `IEditorOptions`.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Editor options passed to `monaco.editor.create` and, when they change, to
+/// `editor.updateOptions`.
+///
+/// Fields left at `None` are not sent, so Monaco keeps its own default (or the
+/// previous value, on update). Anything not covered here can go into `extra`,
+/// which is merged in as raw JSON using Monaco's camelCase names.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_height: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insert_spaces: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word_wrap: Option<WordWrap>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word_wrap_column: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_numbers: Option<LineNumbers>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_numbers_min_chars: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_decorations_width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimap: Option<MinimapOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folding: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glyph_margin: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_beyond_last_line: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smooth_scrolling: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub render_whitespace: Option<RenderWhitespace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rulers: Option<Vec<u32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor_style: Option<CursorStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab_completion: Option<TabCompletion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bracket_pair_colorization: Option<BracketPairColorization>,
+    /// Whether tokens from a semantic tokens provider are shown over the tokenizer's.
+    #[serde(rename = "semanticHighlighting.enabled", skip_serializing_if = "Option::is_none")]
+    pub semantic_highlighting: Option<bool>,
+    /// Raw options merged in last, e.g. `{"renderLineHighlight": "gutter"}`.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl EditorOptions {
+    /// The compact look used for short snippets: no minimap or folding, wrapped
+    /// lines, and a narrow gutter.
+    pub fn snippet() -> Self {
+        Self {
+            font_size: Some(14.0),
+            word_wrap: Some(WordWrap::On),
+            line_numbers: Some(LineNumbers::On),
+            line_numbers_min_chars: Some(0),
+            line_decorations_width: Some(10),
+            minimap: Some(MinimapOptions { enabled: false }),
+            folding: Some(false),
+            glyph_margin: Some(false),
+            scroll_beyond_last_line: Some(false),
+            ..Self::default()
+        }
+    }
+
+    /// Sets a raw option that has no typed field.
+    pub fn with_extra(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.extra.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_else(|_| Value::Object(Map::new()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WordWrap {
+    Off,
+    On,
+    /// Wrap at `word_wrap_column`.
+    #[serde(rename = "wordWrapColumn")]
+    Column,
+    /// Wrap at the viewport or `word_wrap_column`, whichever is smaller.
+    Bounded,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LineNumbers {
+    Off,
+    On,
+    Relative,
+    Interval,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RenderWhitespace {
+    None,
+    Boundary,
+    Selection,
+    Trailing,
+    All,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CursorStyle {
+    Line,
+    Block,
+    Underline,
+    LineThin,
+    BlockOutline,
+    UnderlineThin,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TabCompletion {
+    On,
+    Off,
+    OnlySnippets,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct MinimapOptions {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct BracketPairColorization {
+    pub enabled: bool,
+}
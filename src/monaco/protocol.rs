@@ -0,0 +1,325 @@
+//! Wire format between Rust and the dispatcher in `assets/monaco_bridge.js`.
+//!
+//! Rust sends [`Request`]s carrying an [`Op`] and a correlation id; the dispatcher
+//! answers each one with a [`MessageBody::Response`] carrying the same id, and pushes
+//! [`Event`]s on its own. Every message on either side carries [`PROTOCOL_VERSION`].
+//!
+//! New editor operations are plain [`Op::Call`]s built on the Rust side, so the
+//! dispatcher only has to change when the protocol itself does.
+//!
+//! Language providers run the other way round: Monaco asks, the dispatcher forwards
+//! the question as an [`Event::Provide`], and Rust answers it with an [`Op::Resolve`].
+//! When Monaco stops waiting, the dispatcher follows up with an [`Event::Cancel`].
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::custom_language::LanguageDefinition;
+use super::error::MonacoError;
+use super::providers::{ProviderQuery, ProviderSpec};
+use super::types::{LineChange, Range, ViewState};
+
+/// Bumped whenever the shape of any message changes.
+pub const PROTOCOL_VERSION: u32 = 7;
+
+/// A message from Rust to the dispatcher.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Request {
+    pub v: u32,
+    pub id: u64,
+    #[serde(flatten)]
+    pub op: Op,
+}
+
+impl Request {
+    pub fn new(id: u64, op: Op) -> Self {
+        Self { v: PROTOCOL_VERSION, id, op }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Op {
+    /// Load Monaco if needed and create the editor in the instance's container.
+    Create { value: String, options: Value },
+    /// Load Monaco if needed and create a diff editor in the instance's container.
+    /// Its modified side then counts as the instance's editor, so every other op
+    /// works on that side.
+    CreateDiff { original: String, modified: String, options: Value },
+    /// Load Monaco if needed and register `definition`, or update it if its id is
+    /// already registered. Allowed before [`Op::Create`], so models can use it.
+    RegisterLanguage { definition: Box<LanguageDefinition> },
+    /// Bring the content in line with the bound value. Does nothing if it already
+    /// matches, and never echoes a change event back.
+    SyncValue { value: String },
+    /// Call `method` on `target` with `args`. Any `{"$ref": ...}` object inside the
+    /// args is replaced by the live object it names, see [`ObjectRef`].
+    Call { target: Target, method: String, args: Vec<Value> },
+    /// Switch the editor to the model at `uri`, creating it with `value` and `language`
+    /// if the page has none yet, and restore `view_state` on it. Answers with the view
+    /// state of the model shown before.
+    OpenModel { uri: String, value: String, language: String, view_state: Option<ViewState> },
+    /// Dispose the model at `uri` if this instance created it and is not showing it.
+    CloseModel { uri: String },
+    /// Serve `providers` for models of `language` from this instance, replacing
+    /// whatever it served before.
+    SetProviders { language: String, providers: Vec<ProviderSpec> },
+    /// Keep the user from editing `ranges` of the shown model, replacing the ranges
+    /// protected before, and of any model shown later. They move along with edits
+    /// elsewhere; edits reaching into them are taken back, edits at their edges are not.
+    SetProtectedRanges { ranges: Vec<Range> },
+    /// The answer to the [`Event::Provide`] with the same `request`.
+    Resolve { request: u64, result: Value },
+}
+
+impl Op {
+    pub fn call(target: Target, method: &str, args: impl IntoIterator<Item = Value>) -> Self {
+        Op::Call { target, method: method.to_string(), args: args.into_iter().collect() }
+    }
+}
+
+/// The object an [`Op::Call`] is made on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Target {
+    /// The instance's `IStandaloneCodeEditor`.
+    Editor,
+    /// The editor's current `ITextModel`.
+    Model,
+    /// The `monaco.editor` namespace.
+    MonacoEditor,
+    /// The `monaco.languages` namespace.
+    Languages,
+    /// The instance's `IStandaloneDiffEditor`, if it was created with [`Op::CreateDiff`].
+    DiffEditor,
+    /// The diff editor's original model.
+    OriginalModel,
+}
+
+/// A live JS-side object that can be passed as a call argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "$ref", rename_all = "snake_case")]
+pub enum ObjectRef {
+    Editor,
+    Model,
+    OriginalModel,
+    Selection,
+    Position,
+}
+
+impl ObjectRef {
+    pub fn arg(self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+/// A message from the dispatcher to Rust.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Message {
+    pub v: u32,
+    #[serde(flatten)]
+    pub body: MessageBody,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessageBody {
+    /// The answer to the [`Request`] with the same id.
+    Response { id: u64, outcome: Outcome },
+    /// Something that happened in the editor.
+    Event { event: Event },
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Outcome {
+    Ok {
+        #[serde(default)]
+        value: Value,
+    },
+    NotInitialized,
+    Disposed,
+    Exception { message: String, stack: Option<String> },
+}
+
+impl Outcome {
+    pub fn into_result(self) -> Result<Value, MonacoError> {
+        match self {
+            Outcome::Ok { value } => Ok(value),
+            Outcome::NotInitialized => Err(MonacoError::NotInitialized),
+            Outcome::Disposed => Err(MonacoError::Disposed),
+            Outcome::Exception { message, stack } => Err(MonacoError::JsException { message, stack }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// The editor has been created.
+    Ready,
+    /// Loading Monaco or creating the editor failed.
+    Error { message: String, stack: Option<String> },
+    /// The content changed, by the user or by a call.
+    Change { value: String },
+    /// Monaco wants something from one of this instance's providers.
+    Provide { request: u64, query: ProviderQuery },
+    /// Monaco no longer wants the answer to that [`Event::Provide`], typically because
+    /// the user kept typing.
+    Cancel { request: u64 },
+    /// A command was executed, e.g. by clicking a code lens.
+    Command { id: String, args: Vec<Value> },
+    /// A diff editor has recomputed the diff between its sides.
+    Diff { changes: Vec<LineChange> },
+}
+
+impl Message {
+    /// Parses a raw message, rejecting any other protocol version.
+    pub fn parse(raw: Value) -> Result<Self, MonacoError> {
+        let found = raw.get("v").and_then(Value::as_u64).unwrap_or(0) as u32;
+        if found != PROTOCOL_VERSION {
+            return Err(MonacoError::ProtocolMismatch { expected: PROTOCOL_VERSION, found });
+        }
+        Ok(serde_json::from_value(raw)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn requests_are_flat_and_versioned() {
+        let request = Request::new(7, Op::SyncValue { value: "a\"b".into() });
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({ "v": PROTOCOL_VERSION, "id": 7, "op": "sync_value", "value": "a\"b" })
+        );
+    }
+
+    #[test]
+    fn calls_carry_object_refs() {
+        let op = Op::call(
+            Target::Editor,
+            "executeEdits",
+            [json!("rust"), json!([{ "range": ObjectRef::Selection.arg(), "text": "x" }])],
+        );
+        assert_eq!(
+            serde_json::to_value(Request::new(1, op)).unwrap(),
+            json!({
+                "v": PROTOCOL_VERSION,
+                "id": 1,
+                "op": "call",
+                "target": "editor",
+                "method": "executeEdits",
+                "args": ["rust", [{ "range": { "$ref": "selection" }, "text": "x" }]]
+            })
+        );
+    }
+
+    #[test]
+    fn open_model_names_the_model_by_uri() {
+        let op = Op::OpenModel {
+            uri: "file:///snippets/a.js".into(),
+            value: "a()".into(),
+            language: "javascript".into(),
+            view_state: None,
+        };
+        assert_eq!(
+            serde_json::to_value(Request::new(2, op)).unwrap(),
+            json!({
+                "v": PROTOCOL_VERSION,
+                "id": 2,
+                "op": "open_model",
+                "uri": "file:///snippets/a.js",
+                "value": "a()",
+                "language": "javascript",
+                "view_state": null
+            })
+        );
+    }
+
+    #[test]
+    fn protected_ranges_go_out_as_monaco_ranges() {
+        let op = Op::SetProtectedRanges {
+            ranges: vec![
+                Range::lines(1, 2),
+                Range { start_line_number: 5, start_column: 3, end_line_number: 5, end_column: 9 },
+            ],
+        };
+        assert_eq!(
+            serde_json::to_value(Request::new(5, op)).unwrap(),
+            json!({
+                "v": PROTOCOL_VERSION,
+                "id": 5,
+                "op": "set_protected_ranges",
+                "ranges": [
+                    { "startLineNumber": 1, "startColumn": 1, "endLineNumber": 2, "endColumn": u32::MAX },
+                    { "startLineNumber": 5, "startColumn": 3, "endLineNumber": 5, "endColumn": 9 }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn responses_map_to_results() {
+        let ok = Message::parse(json!({
+            "v": PROTOCOL_VERSION, "type": "response", "id": 3,
+            "outcome": { "status": "ok", "value": "text" }
+        }))
+        .unwrap();
+        let MessageBody::Response { id, outcome } = ok.body else { panic!("not a response") };
+        assert_eq!(id, 3);
+        assert_eq!(outcome.into_result(), Ok(json!("text")));
+
+        let thrown = Message::parse(json!({
+            "v": PROTOCOL_VERSION, "type": "response", "id": 4,
+            "outcome": { "status": "exception", "message": "boom", "stack": null }
+        }))
+        .unwrap();
+        let MessageBody::Response { outcome, .. } = thrown.body else { panic!("not a response") };
+        assert_eq!(
+            outcome.into_result(),
+            Err(MonacoError::JsException { message: "boom".into(), stack: None })
+        );
+    }
+
+    #[test]
+    fn ok_without_value_is_null() {
+        let message = Message::parse(json!({
+            "v": PROTOCOL_VERSION, "type": "response", "id": 1, "outcome": { "status": "ok" }
+        }))
+        .unwrap();
+        let MessageBody::Response { outcome, .. } = message.body else { panic!("not a response") };
+        assert_eq!(outcome.into_result(), Ok(Value::Null));
+    }
+
+    #[test]
+    fn events_parse() {
+        let message = Message::parse(json!({
+            "v": PROTOCOL_VERSION, "type": "event", "event": { "event": "change", "value": "x\ny" }
+        }))
+        .unwrap();
+        assert_eq!(message.body, MessageBody::Event { event: Event::Change { value: "x\ny".into() } });
+
+        // Monaco's line changes also carry character changes, which are not needed
+        let message = Message::parse(json!({
+            "v": PROTOCOL_VERSION, "type": "event", "event": { "event": "diff", "changes": [{
+                "originalStartLineNumber": 2, "originalEndLineNumber": 0,
+                "modifiedStartLineNumber": 3, "modifiedEndLineNumber": 4, "charChanges": []
+            }] }
+        }))
+        .unwrap();
+        let MessageBody::Event { event: Event::Diff { changes } } = message.body else { panic!("not a diff event") };
+        assert_eq!(changes[0].original_end_line_number, 0);
+        assert_eq!(changes[0].modified_end_line_number, 4);
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let err = Message::parse(json!({ "v": PROTOCOL_VERSION + 1, "type": "event", "event": { "event": "ready" } }))
+            .unwrap_err();
+        assert_eq!(err, MonacoError::ProtocolMismatch { expected: PROTOCOL_VERSION, found: PROTOCOL_VERSION + 1 });
+    }
+}
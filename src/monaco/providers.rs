@@ -0,0 +1,408 @@
+//! Language features answered from Rust.
+//!
+//! Monaco registers providers per language for the whole page. The dispatcher
+//! registers each kind once per language and forwards every request to the editor
+//! instance owning the model, whose [`Providers`] answer it.
+
+pub mod code_action;
+pub mod code_lens;
+pub mod completion;
+pub mod definition;
+pub mod hover;
+pub mod inlay_hint;
+pub mod inline_completion;
+pub mod rename;
+pub mod semantic_tokens;
+pub mod signature;
+pub mod symbols;
+#[cfg(feature = "tree-sitter")]
+pub mod syntax_tokens;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::protocol::Op;
+
+pub use code_action::{CodeAction, CodeActionProvider, CodeActionRequest};
+pub use code_lens::{CodeLens, CodeLensProvider, LensCommand};
+pub use completion::{CompletionItem, CompletionItemKind, CompletionProvider, CompletionRequest};
+pub use definition::{DefinitionProvider, DefinitionRequest};
+pub use hover::{Hover, HoverProvider, HoverRequest};
+pub use inlay_hint::{InlayHint, InlayHintKind, InlayHintRequest, InlayHintsProvider};
+pub use inline_completion::{InlineCompletion, InlineCompletionProvider, InlineCompletionRequest};
+pub use rename::{RenameProvider, RenameRequest};
+pub use semantic_tokens::{SemanticToken, SemanticTokensLegend, SemanticTokensProvider};
+pub use signature::{
+    ParameterInformation, SignatureHelp, SignatureHelpProvider, SignatureHelpRequest, SignatureInformation,
+};
+pub use symbols::{DocumentSymbol, DocumentSymbolProvider, SymbolKind};
+#[cfg(feature = "tree-sitter")]
+pub use syntax_tokens::TreeSitterTokens;
+
+/// What an instance tells the dispatcher about one provider it serves.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProviderSpec {
+    Completion { trigger_characters: Vec<String> },
+    Hover,
+    SignatureHelp { trigger_characters: Vec<String>, retrigger_characters: Vec<String> },
+    CodeAction { kinds: Vec<String> },
+    CodeLens,
+    DocumentSymbol,
+    Rename,
+    Definition,
+    SemanticTokens { legend: SemanticTokensLegend },
+    InlayHint,
+    InlineCompletion,
+}
+
+/// A request Monaco made of one of the instance's providers.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProviderQuery {
+    Completion(CompletionRequest),
+    Hover(HoverRequest),
+    SignatureHelp(SignatureHelpRequest),
+    CodeAction(CodeActionRequest),
+    CodeLens,
+    DocumentSymbol,
+    Rename(RenameRequest),
+    Definition(DefinitionRequest),
+    SemanticTokens,
+    InlayHint(InlayHintRequest),
+    InlineCompletion(InlineCompletionRequest),
+}
+
+/// The providers a `MonacoEditor` answers for its language, set up builder style:
+///
+/// ```ignore
+/// Providers::new().completion(HostApi).hover(HostApi)
+/// ```
+///
+/// Two sets are equal when they hold the very same providers, so a set kept in a
+/// hook does not re-register on every render.
+#[derive(Clone, Default)]
+pub struct Providers {
+    completion: Option<Rc<dyn CompletionProvider>>,
+    hover: Option<Rc<dyn HoverProvider>>,
+    signature_help: Option<Rc<dyn SignatureHelpProvider>>,
+    code_action: Option<Rc<dyn CodeActionProvider>>,
+    code_lens: Option<Rc<dyn CodeLensProvider>>,
+    document_symbol: Option<Rc<dyn DocumentSymbolProvider>>,
+    rename: Option<Rc<dyn RenameProvider>>,
+    definition: Option<Rc<dyn DefinitionProvider>>,
+    semantic_tokens: Option<Rc<dyn SemanticTokensProvider>>,
+    inlay_hint: Option<Rc<dyn InlayHintsProvider>>,
+    inline_completion: Option<Rc<dyn InlineCompletionProvider>>,
+}
+
+impl Providers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn completion(mut self, provider: impl CompletionProvider + 'static) -> Self {
+        self.completion = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn hover(mut self, provider: impl HoverProvider + 'static) -> Self {
+        self.hover = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn signature_help(mut self, provider: impl SignatureHelpProvider + 'static) -> Self {
+        self.signature_help = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn code_actions(mut self, provider: impl CodeActionProvider + 'static) -> Self {
+        self.code_action = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn code_lenses(mut self, provider: impl CodeLensProvider + 'static) -> Self {
+        self.code_lens = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn document_symbols(mut self, provider: impl DocumentSymbolProvider + 'static) -> Self {
+        self.document_symbol = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn rename(mut self, provider: impl RenameProvider + 'static) -> Self {
+        self.rename = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn definition(mut self, provider: impl DefinitionProvider + 'static) -> Self {
+        self.definition = Some(Rc::new(provider));
+        self
+    }
+
+    /// Also turns on semantic highlighting, unless the editor's options say otherwise.
+    pub fn semantic_tokens(mut self, provider: impl SemanticTokensProvider + 'static) -> Self {
+        self.semantic_tokens = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn inlay_hints(mut self, provider: impl InlayHintsProvider + 'static) -> Self {
+        self.inlay_hint = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn inline_completions(mut self, provider: impl InlineCompletionProvider + 'static) -> Self {
+        self.inline_completion = Some(Rc::new(provider));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.specs().is_empty()
+    }
+
+    pub(crate) fn has_semantic_tokens(&self) -> bool {
+        self.semantic_tokens.is_some()
+    }
+
+    pub(crate) fn specs(&self) -> Vec<ProviderSpec> {
+        let mut specs = Vec::new();
+        if let Some(provider) = &self.completion {
+            specs.push(ProviderSpec::Completion { trigger_characters: provider.trigger_characters() });
+        }
+        if self.hover.is_some() {
+            specs.push(ProviderSpec::Hover);
+        }
+        if let Some(provider) = &self.signature_help {
+            specs.push(ProviderSpec::SignatureHelp {
+                trigger_characters: provider.trigger_characters(),
+                retrigger_characters: provider.retrigger_characters(),
+            });
+        }
+        if let Some(provider) = &self.code_action {
+            specs.push(ProviderSpec::CodeAction { kinds: provider.provided_kinds() });
+        }
+        if self.code_lens.is_some() {
+            specs.push(ProviderSpec::CodeLens);
+        }
+        if self.document_symbol.is_some() {
+            specs.push(ProviderSpec::DocumentSymbol);
+        }
+        if self.rename.is_some() {
+            specs.push(ProviderSpec::Rename);
+        }
+        if self.definition.is_some() {
+            specs.push(ProviderSpec::Definition);
+        }
+        if let Some(provider) = &self.semantic_tokens {
+            specs.push(ProviderSpec::SemanticTokens { legend: provider.legend() });
+        }
+        if self.inlay_hint.is_some() {
+            specs.push(ProviderSpec::InlayHint);
+        }
+        if self.inline_completion.is_some() {
+            specs.push(ProviderSpec::InlineCompletion);
+        }
+        specs
+    }
+
+    /// How long to wait before answering `query`, for queries Monaco repeats as the
+    /// text changes and cancels once they are stale. `None` means right away.
+    pub(crate) fn delay(&self, query: &ProviderQuery) -> Option<Duration> {
+        let delay_ms = match query {
+            ProviderQuery::InlineCompletion(_) => self.inline_completion.as_ref()?.delay_ms(),
+            ProviderQuery::InlayHint(_) => self.inlay_hint.as_ref()?.delay_ms(),
+            ProviderQuery::CodeLens => self.code_lens.as_ref()?.delay_ms(),
+            ProviderQuery::SemanticTokens => self.semantic_tokens.as_ref()?.delay_ms(),
+            _ => return None,
+        };
+        Some(Duration::from_millis(delay_ms))
+    }
+
+    /// The call telling the dispatcher what this set serves for `language`.
+    pub(crate) fn register_op(&self, language: &str) -> Op {
+        Op::SetProviders { language: language.to_string(), providers: self.specs() }
+    }
+
+    /// Answers `query` against `source`, the current content. Queries for a provider
+    /// the set does not have are answered with `null`.
+    pub(crate) fn answer(&self, source: &str, query: &ProviderQuery) -> Value {
+        match query {
+            ProviderQuery::Completion(request) => match &self.completion {
+                Some(provider) => serde_json::to_value(provider.provide_completions(source, request)),
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::Hover(request) => match &self.hover {
+                Some(provider) => serde_json::to_value(provider.provide_hover(source, request)),
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::SignatureHelp(request) => match &self.signature_help {
+                Some(provider) => serde_json::to_value(provider.provide_signature_help(source, request)),
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::CodeAction(request) => match &self.code_action {
+                Some(provider) => serde_json::to_value(provider.provide_code_actions(source, request)),
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::CodeLens => match &self.code_lens {
+                Some(provider) => serde_json::to_value(provider.provide_code_lenses(source)),
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::DocumentSymbol => match &self.document_symbol {
+                Some(provider) => serde_json::to_value(provider.provide_document_symbols(source)),
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::Rename(request) => match &self.rename {
+                Some(provider) => match provider.provide_rename_edits(source, request) {
+                    Ok(edit) => serde_json::to_value(edit).map(|edit| json!({ "edit": edit })),
+                    Err(reason) => Ok(json!({ "reject_reason": reason })),
+                },
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::Definition(request) => match &self.definition {
+                Some(provider) => serde_json::to_value(provider.provide_definition(source, request)),
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::SemanticTokens => match &self.semantic_tokens {
+                Some(provider) => {
+                    let data = semantic_tokens::encode(&provider.provide_semantic_tokens(source));
+                    Ok(json!({ "data": data }))
+                }
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::InlayHint(request) => match &self.inlay_hint {
+                Some(provider) => serde_json::to_value(provider.provide_inlay_hints(source, request)),
+                None => Ok(Value::Null),
+            },
+            ProviderQuery::InlineCompletion(request) => match &self.inline_completion {
+                Some(provider) => serde_json::to_value(provider.provide_inline_completions(source, request)),
+                None => Ok(Value::Null),
+            },
+        }
+        .unwrap_or(Value::Null)
+    }
+}
+
+impl PartialEq for Providers {
+    fn eq(&self, other: &Self) -> bool {
+        same(&self.completion, &other.completion)
+            && same(&self.hover, &other.hover)
+            && same(&self.signature_help, &other.signature_help)
+            && same(&self.code_action, &other.code_action)
+            && same(&self.code_lens, &other.code_lens)
+            && same(&self.document_symbol, &other.document_symbol)
+            && same(&self.rename, &other.rename)
+            && same(&self.definition, &other.definition)
+            && same(&self.semantic_tokens, &other.semantic_tokens)
+            && same(&self.inlay_hint, &other.inlay_hint)
+            && same(&self.inline_completion, &other.inline_completion)
+    }
+}
+
+impl std::fmt::Debug for Providers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.specs()).finish()
+    }
+}
+
+fn same<T: ?Sized>(a: &Option<Rc<T>>, b: &Option<Rc<T>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monaco::types::Position;
+    use serde_json::json;
+
+    struct Keywords;
+
+    impl CompletionProvider for Keywords {
+        fn trigger_characters(&self) -> Vec<String> {
+            vec![".".into()]
+        }
+
+        fn provide_completions(&self, _source: &str, request: &CompletionRequest) -> Vec<CompletionItem> {
+            vec![CompletionItem::new(format!("{}x", request.word), CompletionItemKind::Keyword)]
+        }
+    }
+
+    #[test]
+    fn queries_are_answered_by_the_matching_provider() {
+        let query: ProviderQuery = serde_json::from_value(json!({
+            "kind": "completion",
+            "position": { "lineNumber": 1, "column": 3 },
+            "word": "ho",
+            "trigger_character": null
+        }))
+        .unwrap();
+        let ProviderQuery::Completion(request) = &query else { panic!("not a completion query") };
+        assert_eq!(request.position, Position { line_number: 1, column: 3 });
+
+        let providers = Providers::new().completion(Keywords);
+        assert_eq!(providers.answer("ho", &query)[0]["label"], json!("hox"));
+        assert_eq!(Providers::new().answer("ho", &query), Value::Null);
+    }
+
+    struct Boilerplate;
+
+    impl InlineCompletionProvider for Boilerplate {
+        fn provide_inline_completions(&self, _: &str, _: &InlineCompletionRequest) -> Vec<InlineCompletion> {
+            vec![InlineCompletion::new("();")]
+        }
+    }
+
+    #[test]
+    fn inline_completions_wait_for_typing_to_pause() {
+        let query: ProviderQuery = serde_json::from_value(json!({
+            "kind": "inline_completion",
+            "position": { "lineNumber": 2, "column": 5 },
+            "explicit": false
+        }))
+        .unwrap();
+
+        let providers = Providers::new().inline_completions(Boilerplate);
+        assert_eq!(providers.delay(&query), Some(Duration::from_millis(150)));
+        assert_eq!(providers.answer("", &query), json!([{ "insertText": "();" }]));
+        assert_eq!(Providers::new().delay(&query), None);
+        assert_eq!(providers.delay(&ProviderQuery::CodeLens), None);
+    }
+
+    struct Lenses;
+
+    impl CodeLensProvider for Lenses {
+        fn provide_code_lenses(&self, _source: &str) -> Vec<CodeLens> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn repeated_queries_are_deferred_so_they_can_be_cancelled() {
+        let providers = Providers::new().code_lenses(Lenses).completion(Keywords);
+        assert_eq!(providers.delay(&ProviderQuery::CodeLens), Some(Duration::ZERO));
+        assert_eq!(providers.delay(&ProviderQuery::DocumentSymbol), None);
+        let query = ProviderQuery::InlayHint(InlayHintRequest { range: crate::monaco::types::Range::lines(1, 10) });
+        assert_eq!(providers.delay(&query), None);
+    }
+
+    #[test]
+    fn sets_compare_by_provider_identity() {
+        let providers = Providers::new().completion(Keywords);
+        assert_eq!(providers, providers.clone());
+        assert_ne!(providers, Providers::new().completion(Keywords));
+        assert_eq!(
+            serde_json::to_value(providers.register_op("javascript")).unwrap(),
+            json!({
+                "op": "set_providers",
+                "language": "javascript",
+                "providers": [{ "kind": "completion", "trigger_characters": ["."] }]
+            })
+        );
+    }
+}
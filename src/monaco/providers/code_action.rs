@@ -0,0 +1,82 @@
+//! Quick fixes and refactorings, served through `registerCodeActionProvider`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::monaco::diagnostics::Diagnostic;
+use crate::monaco::types::{Range, WorkspaceEdit};
+
+/// What the lightbulb menu was opened for.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CodeActionRequest {
+    /// The selection, or the cursor as an empty range.
+    pub range: Range,
+    /// Markers overlapping `range`, from every owner, not only the validator.
+    pub markers: Vec<Diagnostic>,
+    /// Only actions of this kind are wanted, e.g. `quickfix`.
+    pub only: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeAction {
+    pub title: String,
+    /// A hierarchical kind such as `quickfix` or `refactor.extract`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// The markers this action fixes.
+    pub diagnostics: Vec<Diagnostic>,
+    pub edit: WorkspaceEdit,
+    /// Preferred fixes are applied by the "auto fix" command.
+    pub is_preferred: bool,
+}
+
+impl CodeAction {
+    /// A quick fix applying `edit`.
+    pub fn quick_fix(title: impl Into<String>, edit: WorkspaceEdit) -> Self {
+        Self {
+            title: title.into(),
+            kind: Some("quickfix".to_string()),
+            diagnostics: Vec::new(),
+            edit,
+            is_preferred: false,
+        }
+    }
+
+    pub fn fixing(mut self, diagnostic: Diagnostic) -> Self {
+        self.diagnostics.push(diagnostic);
+        self
+    }
+
+    pub fn preferred(mut self) -> Self {
+        self.is_preferred = true;
+        self
+    }
+}
+
+/// Code actions for a language.
+pub trait CodeActionProvider {
+    /// Kinds of action this provider can return, letting Monaco skip it otherwise.
+    fn provided_kinds(&self) -> Vec<String> {
+        vec!["quickfix".to_string()]
+    }
+
+    /// The actions for `request`, given `source`, the current content.
+    fn provide_code_actions(&self, source: &str, request: &CodeActionRequest) -> Vec<CodeAction>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monaco::types::TextEdit;
+
+    #[test]
+    fn preferred_fixes_are_flagged_for_auto_fix() {
+        let range = Range { start_line_number: 1, start_column: 1, end_line_number: 1, end_column: 11 };
+        let edit = WorkspaceEdit { edits: vec![TextEdit::new(range, "host.log")] };
+        let action = serde_json::to_value(CodeAction::quick_fix("Use host.log", edit).preferred()).unwrap();
+        assert_eq!(action["isPreferred"], true);
+        assert_eq!(action["kind"], "quickfix");
+        assert_eq!(action["edit"]["edits"][0]["text"], "host.log");
+        assert_eq!(action["diagnostics"].as_array().map(Vec::len), Some(0));
+    }
+}
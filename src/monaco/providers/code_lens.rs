@@ -0,0 +1,72 @@
+//! Code lenses, served through `registerCodeLensProvider`.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::monaco::types::Range;
+
+/// A clickable label shown above `range`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CodeLens {
+    pub range: Range,
+    pub command: LensCommand,
+}
+
+/// What a lens shows, and the command it runs when clicked. The command is looked
+/// up in the editor's [`Commands`](crate::monaco::Commands).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LensCommand {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tooltip: Option<String>,
+    pub arguments: Vec<Value>,
+}
+
+impl CodeLens {
+    pub fn new(range: Range, title: impl Into<String>, command_id: impl Into<String>) -> Self {
+        Self {
+            range,
+            command: LensCommand { id: command_id.into(), title: title.into(), tooltip: None, arguments: Vec::new() },
+        }
+    }
+
+    pub fn with_argument(mut self, argument: impl Into<Value>) -> Self {
+        self.command.arguments.push(argument.into());
+        self
+    }
+
+    pub fn with_tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.command.tooltip = Some(tooltip.into());
+        self
+    }
+}
+
+/// Code lenses for a language. Monaco asks again after every change.
+pub trait CodeLensProvider {
+    /// How long to hold a request; one Monaco cancels meanwhile is never answered.
+    fn delay_ms(&self) -> u64 {
+        0
+    }
+
+    fn provide_code_lenses(&self, source: &str) -> Vec<CodeLens>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lens_commands_keep_their_id_and_arguments() {
+        let lens = CodeLens::new(Range::lines(3, 3), "▶ Run", "snippet.run")
+            .with_argument("main")
+            .with_tooltip("Run main");
+        let value = serde_json::to_value(lens).unwrap();
+        // The dispatcher passes `id` on as the first argument of the instance's command
+        assert_eq!(value["command"]["id"], "snippet.run");
+        assert_eq!(value["command"]["arguments"][0], "main");
+        assert_eq!(value["command"]["title"], "▶ Run");
+        assert_eq!(value["command"]["tooltip"], "Run main");
+        assert_eq!(value["range"]["startLineNumber"], 3);
+    }
+}
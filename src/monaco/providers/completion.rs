@@ -0,0 +1,128 @@
+//! Completion items, served through `registerCompletionItemProvider`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::monaco::types::{Position, Range};
+
+/// Where completion was asked for.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CompletionRequest {
+    pub position: Position,
+    /// The part of the word before the cursor, empty between words.
+    pub word: String,
+    /// The character that triggered completion, if it was not invoked explicitly.
+    pub trigger_character: Option<String>,
+}
+
+/// Monaco's `CompletionItemKind`, which picks the icon shown next to the item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum CompletionItemKind {
+    Method,
+    Function,
+    Constructor,
+    Field,
+    Variable,
+    Class,
+    Struct,
+    Interface,
+    Module,
+    Property,
+    Event,
+    Operator,
+    Unit,
+    Value,
+    Constant,
+    Enum,
+    EnumMember,
+    Keyword,
+    Text,
+    Color,
+    File,
+    Reference,
+    Folder,
+    TypeParameter,
+    Snippet,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: CompletionItemKind,
+    /// Text inserted when the item is accepted.
+    pub insert_text: String,
+    /// Whether `insert_text` is a snippet with tab-stops such as `${1:name}` and `$0`.
+    pub is_snippet: bool,
+    /// Short text shown next to the label, e.g. a signature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Markdown shown in the details pane.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
+    /// The text replaced on accept; defaults to the word at the cursor.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<Range>,
+}
+
+impl CompletionItem {
+    /// An item inserting its label as is.
+    pub fn new(label: impl Into<String>, kind: CompletionItemKind) -> Self {
+        let label = label.into();
+        Self {
+            insert_text: label.clone(),
+            label,
+            kind,
+            is_snippet: false,
+            detail: None,
+            documentation: None,
+            range: None,
+        }
+    }
+
+    /// Inserts `snippet` instead of the label, with its tab-stops active.
+    pub fn with_snippet(mut self, snippet: impl Into<String>) -> Self {
+        self.insert_text = snippet.into();
+        self.is_snippet = true;
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn with_documentation(mut self, markdown: impl Into<String>) -> Self {
+        self.documentation = Some(markdown.into());
+        self
+    }
+}
+
+/// Completion logic for a language.
+pub trait CompletionProvider {
+    /// Characters that open the suggestion list on their own, such as `.`.
+    fn trigger_characters(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Items for `request`, given `source`, the current content of the editor.
+    fn provide_completions(&self, source: &str, request: &CompletionRequest) -> Vec<CompletionItem>;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippet_items_are_flagged_for_the_dispatcher() {
+        let item = CompletionItem::new("log", CompletionItemKind::Function)
+            .with_snippet("log(${1:message})$0")
+            .with_documentation("Writes to the host log.");
+        let value = serde_json::to_value(item).unwrap();
+        // `isSnippet` picks the insert rule, and `kind` is looked up by name
+        assert_eq!(value["isSnippet"], true);
+        assert_eq!(value["insertText"], "log(${1:message})$0");
+        assert_eq!(value["kind"], "Function");
+        assert!(value.get("range").is_none());
+    }
+}
@@ -0,0 +1,47 @@
+//! Go to definition, served through `registerDefinitionProvider`.
+
+use serde::Deserialize;
+
+use crate::monaco::types::{Location, OpenDocument, Position};
+
+/// A lookup started in the model `uri`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct DefinitionRequest {
+    pub uri: String,
+    pub position: Position,
+    /// The word under the cursor, if there is one.
+    pub word: Option<String>,
+    /// Every model open on the page, including the one the lookup started in.
+    pub documents: Vec<OpenDocument>,
+}
+
+/// Definitions for a language. Locations in another model open that model's editor.
+pub trait DefinitionProvider {
+    fn provide_definition(&self, source: &str, request: &DefinitionRequest) -> Vec<Location>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monaco::types::Range;
+    use serde_json::json;
+
+    #[test]
+    fn requests_list_the_open_documents() {
+        let request: DefinitionRequest = serde_json::from_value(json!({
+            "uri": "file:///a.js",
+            "position": { "lineNumber": 4, "column": 9 },
+            "word": "total",
+            "documents": [{ "uri": "file:///b.js", "language": "javascript", "value": "let total = 1;" }]
+        }))
+        .unwrap();
+        assert_eq!(request.position, Position { line_number: 4, column: 9 });
+        assert_eq!(request.documents[0].uri, "file:///b.js");
+
+        // The dispatcher parses `uri` and hands `range` to Monaco as is
+        let range = Range { start_line_number: 1, start_column: 5, end_line_number: 1, end_column: 10 };
+        let location = serde_json::to_value(Location { uri: "file:///b.js".to_string(), range }).unwrap();
+        assert_eq!(location["uri"], "file:///b.js");
+        assert_eq!(location["range"]["endColumn"], 10);
+    }
+}
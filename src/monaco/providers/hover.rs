@@ -0,0 +1,54 @@
+//! Hover cards, served through `registerHoverProvider`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::monaco::types::{Position, Range};
+
+/// Where the mouse rests.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct HoverRequest {
+    pub position: Position,
+    /// The whole word under the mouse, if there is one.
+    pub word: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Hover {
+    /// Markdown sections, shown one below the other.
+    pub contents: Vec<String>,
+    /// The text the card belongs to; defaults to the word under the mouse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<Range>,
+}
+
+impl Hover {
+    pub fn new(contents: impl IntoIterator<Item = String>) -> Self {
+        Self { contents: contents.into_iter().collect(), range: None }
+    }
+
+    pub fn with_range(mut self, range: Range) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+/// Hover information for a language.
+pub trait HoverProvider {
+    /// The card for `request`, given `source`, the current content, or `None` for no card.
+    fn provide_hover(&self, source: &str, request: &HoverRequest) -> Option<Hover>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cards_carry_markdown_sections_and_an_optional_range() {
+        let range = Range { start_line_number: 2, start_column: 5, end_line_number: 2, end_column: 8 };
+        let card = serde_json::to_value(Hover::new(["**log**".to_string()]).with_range(range)).unwrap();
+        // The dispatcher wraps each section as `{ value }` and falls back to the word's range
+        assert_eq!(card["contents"][0], "**log**");
+        assert_eq!(card["range"]["startColumn"], 5);
+        assert!(serde_json::to_value(Hover::new([])).unwrap().get("range").is_none());
+    }
+}
@@ -0,0 +1,84 @@
+//! Inlay hints, served through `registerInlayHintsProvider`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::monaco::types::{Position, Range};
+
+/// The part of the document Monaco wants hints for, usually what is on screen.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct InlayHintRequest {
+    pub range: Range,
+}
+
+/// Monaco's `InlayHintKind`, which only changes the styling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum InlayHintKind {
+    Type,
+    Parameter,
+}
+
+/// A label shown inline before `position`, without being part of the text.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHint {
+    pub position: Position,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<InlayHintKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tooltip: Option<String>,
+    pub padding_left: bool,
+    pub padding_right: bool,
+}
+
+impl InlayHint {
+    pub fn new(position: Position, label: impl Into<String>) -> Self {
+        Self { position, label: label.into(), kind: None, tooltip: None, padding_left: false, padding_right: false }
+    }
+
+    /// `name:` in front of an argument.
+    pub fn parameter(position: Position, name: &str) -> Self {
+        Self { kind: Some(InlayHintKind::Parameter), padding_right: true, ..Self::new(position, format!("{name}:")) }
+    }
+
+    /// `: type` after a binding.
+    pub fn type_hint(position: Position, type_name: &str) -> Self {
+        Self { kind: Some(InlayHintKind::Type), ..Self::new(position, format!(": {type_name}")) }
+    }
+
+    pub fn with_tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+}
+
+/// Inlay hints for a language. Monaco asks again after changes, once typing pauses.
+pub trait InlayHintsProvider {
+    /// Extra wait before answering, on top of Monaco's own pause after typing.
+    fn delay_ms(&self) -> u64 {
+        0
+    }
+
+    fn provide_inlay_hints(&self, source: &str, request: &InlayHintRequest) -> Vec<InlayHint>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hint_kinds_and_padding_use_monaco_names() {
+        let position = Position { line_number: 2, column: 16 };
+        let hint = InlayHint::parameter(position, "url").with_tooltip("The address");
+        let parameter = serde_json::to_value(hint).unwrap();
+        assert_eq!(parameter["kind"], "Parameter");
+        assert_eq!(parameter["label"], "url:");
+        assert_eq!(parameter["paddingRight"], true);
+        assert_eq!(parameter["tooltip"], "The address");
+        assert_eq!(parameter["position"]["lineNumber"], 2);
+
+        let plain = serde_json::to_value(InlayHint::new(position, "…")).unwrap();
+        assert!(plain.get("kind").is_none());
+        assert_eq!(plain["paddingLeft"], false);
+    }
+}
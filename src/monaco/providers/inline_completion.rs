@@ -0,0 +1,65 @@
+//! Ghost-text suggestions, served through `registerInlineCompletionsProvider`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::monaco::types::{Position, Range};
+
+/// Where a suggestion was asked for.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct InlineCompletionRequest {
+    pub position: Position,
+    /// Whether the user asked explicitly, rather than Monaco asking while they type.
+    pub explicit: bool,
+}
+
+/// Text shown greyed out at the cursor and inserted on Tab.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineCompletion {
+    /// Everything the range ends up containing, including what is already typed.
+    pub insert_text: String,
+    /// What the suggestion replaces; defaults to an empty range at the cursor.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<Range>,
+}
+
+impl InlineCompletion {
+    pub fn new(insert_text: impl Into<String>) -> Self {
+        Self { insert_text: insert_text.into(), range: None }
+    }
+
+    pub fn replacing(mut self, range: Range) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+/// Inline suggestions for a language.
+///
+/// Monaco asks on every keystroke. `MonacoEditor` only calls this once typing
+/// pauses, and drops requests the user has typed past.
+pub trait InlineCompletionProvider {
+    /// How long typing has to pause before a request is answered.
+    fn delay_ms(&self) -> u64 {
+        150
+    }
+
+    fn provide_inline_completions(&self, source: &str, request: &InlineCompletionRequest) -> Vec<InlineCompletion>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggestions_default_to_the_cursor() {
+        let at_cursor = serde_json::to_value(InlineCompletion::new("url, timeoutMs);")).unwrap();
+        assert_eq!(at_cursor["insertText"], "url, timeoutMs);");
+        // Without a range the dispatcher puts the suggestion at the cursor
+        assert!(at_cursor.get("range").is_none());
+
+        let range = Range { start_line_number: 1, start_column: 1, end_line_number: 1, end_column: 4 };
+        let replacing = serde_json::to_value(InlineCompletion::new("host").replacing(range)).unwrap();
+        assert_eq!(replacing["range"]["endColumn"], 4);
+    }
+}
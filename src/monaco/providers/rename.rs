@@ -0,0 +1,56 @@
+//! Symbol renames, served through `registerRenameProvider`.
+
+use serde::Deserialize;
+
+use crate::monaco::types::{OpenDocument, Position, WorkspaceEdit};
+
+/// A rename started in the model `uri`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct RenameRequest {
+    pub uri: String,
+    pub position: Position,
+    /// The word being renamed, if the cursor is on one.
+    pub word: Option<String>,
+    pub new_name: String,
+    /// Every model open on the page, including the one the rename started in.
+    pub documents: Vec<OpenDocument>,
+}
+
+/// Renames for a language. Edits may target any of the open models by URI.
+pub trait RenameProvider {
+    /// The edits carrying out `request`, or why the symbol cannot be renamed.
+    fn provide_rename_edits(&self, source: &str, request: &RenameRequest) -> Result<WorkspaceEdit, String>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monaco::types::{Range, TextEdit};
+    use serde_json::json;
+
+    #[test]
+    fn edits_name_the_model_they_belong_to() {
+        let request: RenameRequest = serde_json::from_value(json!({
+            "uri": "file:///a.js",
+            "position": { "lineNumber": 1, "column": 6 },
+            "word": "total",
+            "new_name": "sum",
+            "documents": []
+        }))
+        .unwrap();
+        assert_eq!(request.new_name, "sum");
+
+        let range = Range { start_line_number: 1, start_column: 5, end_line_number: 1, end_column: 10 };
+        let edit = WorkspaceEdit {
+            edits: vec![
+                TextEdit::new(range, "sum"),
+                TextEdit { uri: Some("file:///b.js".to_string()), ..TextEdit::new(range, "sum") },
+            ],
+        };
+        let value = serde_json::to_value(&edit).unwrap();
+        // Without a `uri` the dispatcher edits the model the rename started in
+        assert!(value["edits"][0].get("uri").is_none());
+        assert_eq!(value["edits"][1]["uri"], "file:///b.js");
+        assert_eq!(serde_json::from_value::<WorkspaceEdit>(value).unwrap(), edit);
+    }
+}
@@ -0,0 +1,124 @@
+//! Semantic highlighting, served through `registerDocumentSemanticTokensProvider`.
+
+use serde::Serialize;
+
+/// The token types and modifiers a provider reports, by name. Tokens refer to
+/// types by index and to modifiers by bit.
+///
+/// The names are looked up in the theme's token rules, with modifiers appended, so
+/// `function` with `defaultLibrary` is styled by a `function.defaultLibrary` rule or
+/// else by `function`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokensLegend {
+    pub token_types: Vec<String>,
+    pub token_modifiers: Vec<String>,
+}
+
+impl SemanticTokensLegend {
+    pub fn new<T: Into<String>, M: Into<String>>(
+        token_types: impl IntoIterator<Item = T>,
+        token_modifiers: impl IntoIterator<Item = M>,
+    ) -> Self {
+        Self {
+            token_types: token_types.into_iter().map(Into::into).collect(),
+            token_modifiers: token_modifiers.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn token_type(&self, name: &str) -> Option<u32> {
+        self.token_types.iter().position(|token_type| token_type == name).map(|index| index as u32)
+    }
+
+    /// The bit set for `names`, ignoring names the legend does not have.
+    pub fn modifiers(&self, names: &[&str]) -> u32 {
+        names
+            .iter()
+            .filter_map(|name| self.token_modifiers.iter().position(|modifier| modifier == name))
+            .fold(0, |bits, index| bits | (1 << index))
+    }
+}
+
+/// One highlighted span. Tokens cannot span lines or overlap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SemanticToken {
+    /// 1-based, like [`Position`](crate::monaco::Position).
+    pub line_number: u32,
+    /// 1-based, counted in UTF-16 code units like Monaco's columns.
+    pub column: u32,
+    /// In UTF-16 code units.
+    pub length: u32,
+    /// Index into the legend's `token_types`.
+    pub token_type: u32,
+    /// Bit set over the legend's `token_modifiers`.
+    pub modifiers: u32,
+}
+
+impl SemanticToken {
+    pub fn new(line_number: u32, column: u32, length: u32, token_type: u32) -> Self {
+        Self { line_number, column, length, token_type, modifiers: 0 }
+    }
+
+    pub fn with_modifiers(mut self, modifiers: u32) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+}
+
+/// Semantic tokens for a language, computed for the whole document. Monaco asks
+/// again shortly after every change.
+///
+/// The legend is registered with the language, so every editor serving semantic
+/// tokens for that language should use the same one.
+pub trait SemanticTokensProvider {
+    fn legend(&self) -> SemanticTokensLegend;
+
+    /// Extra wait before tokenizing, so a burst of edits is only tokenized once.
+    fn delay_ms(&self) -> u64 {
+        0
+    }
+
+    fn provide_semantic_tokens(&self, source: &str) -> Vec<SemanticToken>;
+}
+
+/// Monaco's `SemanticTokens.data`: five numbers per token, with each token's line
+/// relative to the previous token and its start relative to the previous token on
+/// the same line.
+pub(crate) fn encode(tokens: &[SemanticToken]) -> Vec<u32> {
+    let mut tokens = tokens.to_vec();
+    tokens.sort_by_key(|token| (token.line_number, token.column));
+
+    let mut data = Vec::with_capacity(tokens.len() * 5);
+    let (mut line, mut start) = (0, 0);
+    for token in tokens {
+        let token_line = token.line_number.saturating_sub(1);
+        let token_start = token.column.saturating_sub(1);
+        if token_line != line {
+            start = 0;
+        }
+        data.extend([token_line - line, token_start - start, token.length, token.token_type, token.modifiers]);
+        line = token_line;
+        start = token_start;
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_delta_encoded_in_document_order() {
+        let legend = SemanticTokensLegend::new(["keyword", "function"], ["declaration", "readonly"]);
+        assert_eq!(legend.token_type("function"), Some(1));
+        assert_eq!(legend.modifiers(&["readonly", "static"]), 0b10);
+
+        let tokens = [
+            SemanticToken::new(3, 5, 2, 0),
+            SemanticToken::new(1, 10, 3, 1).with_modifiers(0b01),
+            SemanticToken::new(1, 1, 8, 0),
+        ];
+        assert_eq!(encode(&tokens), vec![0, 0, 8, 0, 0, 0, 9, 3, 1, 1, 2, 4, 2, 0, 0]);
+        assert!(encode(&[]).is_empty());
+    }
+}
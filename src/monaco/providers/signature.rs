@@ -0,0 +1,83 @@
+//! Parameter hints, served through `registerSignatureHelpProvider`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::monaco::types::Position;
+
+/// Where parameter hints were asked for.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct SignatureHelpRequest {
+    pub position: Position,
+    /// The character that opened or updated the hints, if any.
+    pub trigger_character: Option<String>,
+    /// Whether hints are already showing and are being updated.
+    pub is_retrigger: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureHelp {
+    pub signatures: Vec<SignatureInformation>,
+    pub active_signature: u32,
+    /// Index into the active signature's parameters of the one to highlight.
+    pub active_parameter: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SignatureInformation {
+    /// The full signature, e.g. `store(key: string, value: any): void`.
+    pub label: String,
+    /// Markdown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
+    pub parameters: Vec<ParameterInformation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ParameterInformation {
+    /// The parameter as written in the signature label, which is what gets highlighted.
+    pub label: String,
+    /// Markdown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
+}
+
+/// Parameter hints for a language.
+pub trait SignatureHelpProvider {
+    /// Characters that open the hints.
+    fn trigger_characters(&self) -> Vec<String> {
+        vec!["(".to_string(), ",".to_string()]
+    }
+
+    /// Characters that update hints already showing, on top of the trigger characters.
+    fn retrigger_characters(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// The hints for `request`, given `source`, the current content, or `None` to hide them.
+    fn provide_signature_help(&self, source: &str, request: &SignatureHelpRequest) -> Option<SignatureHelp>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn active_indices_use_monaco_names() {
+        let help = SignatureHelp {
+            signatures: vec![SignatureInformation {
+                label: "store(key: string, value: any): void".to_string(),
+                documentation: None,
+                parameters: vec![ParameterInformation { label: "key: string".to_string(), documentation: None }],
+            }],
+            active_signature: 0,
+            active_parameter: 1,
+        };
+        let value = serde_json::to_value(help).unwrap();
+        assert_eq!(value["activeSignature"], 0);
+        assert_eq!(value["activeParameter"], 1);
+        assert_eq!(value["signatures"][0]["parameters"][0]["label"], "key: string");
+        // Missing documentation is left out rather than sent as null
+        assert!(value["signatures"][0].get("documentation").is_none());
+    }
+}
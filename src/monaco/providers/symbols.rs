@@ -0,0 +1,90 @@
+//! Document outline, served through `registerDocumentSymbolProvider`.
+
+use serde::Serialize;
+
+use crate::monaco::types::Range;
+
+/// Monaco's `SymbolKind`, which picks the icon in the outline and breadcrumbs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum SymbolKind {
+    File,
+    Module,
+    Namespace,
+    Package,
+    Class,
+    Method,
+    Property,
+    Field,
+    Constructor,
+    Enum,
+    Interface,
+    Function,
+    Variable,
+    Constant,
+    String,
+    Number,
+    Boolean,
+    Array,
+    Object,
+    Key,
+    Null,
+    EnumMember,
+    Struct,
+    Event,
+    Operator,
+    TypeParameter,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSymbol {
+    pub name: String,
+    /// Shown next to the name, e.g. a signature.
+    pub detail: String,
+    pub kind: SymbolKind,
+    /// The whole declaration, body included.
+    pub range: Range,
+    /// The part to select and reveal, usually the name.
+    pub selection_range: Range,
+    pub children: Vec<DocumentSymbol>,
+}
+
+impl DocumentSymbol {
+    pub fn new(name: impl Into<String>, kind: SymbolKind, range: Range, selection_range: Range) -> Self {
+        Self { name: name.into(), detail: String::new(), kind, range, selection_range, children: Vec::new() }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<DocumentSymbol>) -> Self {
+        self.children = children;
+        self
+    }
+}
+
+/// The symbols of a document, for Monaco's outline and for [`OutlinePanel`](crate::components::OutlinePanel).
+pub trait DocumentSymbolProvider {
+    fn provide_document_symbols(&self, source: &str) -> Vec<DocumentSymbol>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_symbols_use_monaco_names() {
+        let name = Range { start_line_number: 2, start_column: 5, end_line_number: 2, end_column: 8 };
+        let method = DocumentSymbol::new("run", SymbolKind::Method, Range::lines(2, 4), name);
+        let class = DocumentSymbol::new("Job", SymbolKind::Class, Range::lines(1, 5), Range::lines(1, 1))
+            .with_children(vec![method]);
+        let value = serde_json::to_value(class).unwrap();
+        // Kinds go out by name, for the dispatcher to look up in `monaco.languages.SymbolKind`
+        assert_eq!(value["kind"], "Class");
+        assert_eq!(value["children"][0]["kind"], "Method");
+        assert_eq!(value["children"][0]["selectionRange"]["startColumn"], 5);
+        assert_eq!(value["detail"], "");
+    }
+}
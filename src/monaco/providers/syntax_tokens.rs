@@ -0,0 +1,169 @@
+//! Semantic tokens from a tree-sitter grammar compiled into the binary.
+//!
+//! Needs the `tree-sitter` feature.
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Language, Parser, Query, QueryCursor, QueryError};
+
+use super::semantic_tokens::{SemanticToken, SemanticTokensLegend, SemanticTokensProvider};
+
+/// Token types reported, named like LSP's so themes can style them without extra rules.
+const TOKEN_TYPES: &[&str] = &[
+    "comment", "string", "number", "regexp", "keyword", "operator", "type", "class", "function", "method",
+    "variable", "parameter", "property", "macro", "label",
+];
+
+const TOKEN_MODIFIERS: &[&str] = &["readonly", "defaultLibrary"];
+
+/// Highlights a document by running a grammar's highlight query over its syntax
+/// tree, so nested constructs such as template literals come out right.
+///
+/// Capture names follow tree-sitter's conventions (`function.method`,
+/// `variable.builtin`, ...). Punctuation and other captures without a matching token
+/// type are left to the Monarch tokenizer, even inside a token such as a string.
+pub struct TreeSitterTokens {
+    language: Language,
+    query: Query,
+    /// Token type and modifiers per capture of the query, `None` for captures left to Monarch.
+    captures: Vec<Option<(u32, u32)>>,
+    legend: SemanticTokensLegend,
+}
+
+impl TreeSitterTokens {
+    pub fn new(language: impl Into<Language>, highlights_query: &str) -> Result<Self, QueryError> {
+        let language = language.into();
+        let query = Query::new(&language, highlights_query)?;
+        let legend = SemanticTokensLegend::new(TOKEN_TYPES.iter().copied(), TOKEN_MODIFIERS.iter().copied());
+        let captures = query.capture_names().iter().map(|name| token_for_capture(&legend, name)).collect();
+        Ok(Self { language, query, captures, legend })
+    }
+
+    /// The JavaScript grammar with its bundled highlight query.
+    pub fn javascript() -> Self {
+        Self::new(tree_sitter_javascript::LANGUAGE, tree_sitter_javascript::HIGHLIGHT_QUERY)
+            .expect("the bundled JavaScript highlight query compiles")
+    }
+}
+
+/// Maps a capture name such as `function.builtin` to a token type and modifiers.
+fn token_for_capture(legend: &SemanticTokensLegend, name: &str) -> Option<(u32, u32)> {
+    let (token_type, modifiers): (&str, &[&str]) = match name {
+        "function.method" | "method" => ("method", &[]),
+        "function.builtin" => ("function", &["defaultLibrary"]),
+        "function.macro" => ("macro", &[]),
+        "variable.builtin" => ("variable", &["defaultLibrary"]),
+        "variable.parameter" => ("parameter", &[]),
+        "constant" => ("variable", &["readonly"]),
+        "constant.builtin" => ("keyword", &[]),
+        "constructor" | "type.class" => ("class", &[]),
+        "string.regex" | "string.regexp" => ("regexp", &[]),
+        "type.builtin" => ("type", &["defaultLibrary"]),
+        _ => (name.split('.').next().unwrap_or(name), &[]),
+    };
+    Some((legend.token_type(token_type)?, legend.modifiers(modifiers)))
+}
+
+impl SemanticTokensProvider for TreeSitterTokens {
+    fn legend(&self) -> SemanticTokensLegend {
+        self.legend.clone()
+    }
+
+    fn provide_semantic_tokens(&self, source: &str) -> Vec<SemanticToken> {
+        let mut parser = Parser::new();
+        if parser.set_language(&self.language).is_err() {
+            return Vec::new();
+        }
+        let Some(tree) = parser.parse(source, None) else { return Vec::new() };
+
+        // Every captured node, outermost first. When several patterns capture the same
+        // node the later one wins, as in tree-sitter's own highlighter.
+        let mut spans = BTreeSet::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, tree.root_node(), source.as_bytes());
+        while let Some(query_match) = matches.next() {
+            for capture in query_match.captures {
+                let node = capture.node;
+                let length = node.end_byte() - node.start_byte();
+                let token = self.captures[capture.index as usize];
+                spans.insert((Reverse(length), node.start_byte(), query_match.pattern_index, token));
+            }
+        }
+
+        // Inner nodes paint over outer ones, which leaves non-overlapping runs
+        let mut painted: Vec<Option<usize>> = vec![None; source.len()];
+        let spans: Vec<_> = spans.into_iter().collect();
+        for (index, &(Reverse(length), start, _, _)) in spans.iter().enumerate() {
+            painted[start..start + length].fill(Some(index));
+        }
+
+        let mut tokens = Vec::new();
+        let mut line_start = 0;
+        for (line_index, raw_line) in source.split('\n').enumerate() {
+            let line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+            let mut offset = 0;
+            while offset < line.len() {
+                let Some(span) = painted[line_start + offset] else {
+                    offset += 1;
+                    continue;
+                };
+                let run = painted[line_start + offset..line_start + line.len()]
+                    .iter()
+                    .take_while(|painted| **painted == Some(span))
+                    .count();
+                let Some((token_type, modifiers)) = spans[span].3 else {
+                    offset += run;
+                    continue;
+                };
+                tokens.push(
+                    SemanticToken::new(
+                        line_index as u32 + 1,
+                        utf16_len(&line[..offset]) + 1,
+                        utf16_len(&line[offset..offset + run]),
+                        token_type,
+                    )
+                    .with_modifiers(modifiers),
+                );
+                offset += run;
+            }
+            line_start += raw_line.len() + 1;
+        }
+        tokens
+    }
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_nodes_split_their_parents_into_line_tokens() {
+        let tokens = TreeSitterTokens::javascript();
+        let legend = tokens.legend();
+        let kind = |name: &str| legend.token_type(name).unwrap();
+        let source = "/* a\n   b */ log(`é ${name}`);";
+
+        let found: Vec<_> = tokens
+            .provide_semantic_tokens(source)
+            .into_iter()
+            .map(|token| (token.line_number, token.column, token.length, token.token_type))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (1, 1, 4, kind("comment")),
+                (2, 1, 7, kind("comment")),
+                (2, 9, 3, kind("function")),
+                (2, 13, 3, kind("string")),
+                (2, 18, 4, kind("variable")),
+                (2, 23, 1, kind("string")),
+            ]
+        );
+    }
+}
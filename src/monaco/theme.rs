@@ -0,0 +1,355 @@
+//! Editor themes: Monaco's built-in ones, custom ones defined from Rust, and
+//! conversion from VS Code color themes.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::protocol::{Op, Target};
+
+/// The themes Monaco ships with, which every custom theme builds on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub enum BaseTheme {
+    #[serde(rename = "vs")]
+    Vs,
+    #[default]
+    #[serde(rename = "vs-dark")]
+    VsDark,
+    #[serde(rename = "hc-black")]
+    HcBlack,
+    #[serde(rename = "hc-light")]
+    HcLight,
+}
+
+impl BaseTheme {
+    pub fn name(self) -> &'static str {
+        match self {
+            BaseTheme::Vs => "vs",
+            BaseTheme::VsDark => "vs-dark",
+            BaseTheme::HcBlack => "hc-black",
+            BaseTheme::HcLight => "hc-light",
+        }
+    }
+}
+
+/// How one kind of token is colored, Monaco's `ITokenThemeRule`.
+/// Colors are hex without the leading `#`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenRule {
+    pub token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foreground: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    /// Space separated `italic`, `bold`, `underline`, `strikethrough`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_style: Option<String>,
+}
+
+/// A custom theme, registered with `monaco.editor.defineTheme` under `name`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MonacoTheme {
+    #[serde(skip)]
+    pub name: String,
+    pub base: BaseTheme,
+    /// Whether rules and colors not given here fall back to `base`.
+    pub inherit: bool,
+    pub rules: Vec<TokenRule>,
+    /// Workbench color ids such as `editor.background`, mapped to `#rrggbb[aa]`.
+    pub colors: BTreeMap<String, String>,
+}
+
+impl MonacoTheme {
+    /// An empty theme inheriting everything from `base`. Monaco only accepts
+    /// letters, digits and dashes in theme names, so `name` is cleaned up.
+    pub fn new(name: &str, base: BaseTheme) -> Self {
+        Self { name: theme_id(name), base, inherit: true, rules: Vec::new(), colors: BTreeMap::new() }
+    }
+
+    pub fn with_rule(mut self, rule: TokenRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_color(mut self, id: &str, color: &str) -> Self {
+        self.colors.insert(id.to_string(), color.to_string());
+        self
+    }
+
+    /// Converts a VS Code color theme (the JSON, with or without comments).
+    pub fn from_vscode_json(source: &str) -> serde_json::Result<Self> {
+        let theme: VsCodeTheme = serde_json::from_str(&strip_jsonc(source))?;
+        Ok(theme.into_monaco())
+    }
+
+    /// Reads and converts a VS Code color theme file.
+    pub fn load_vscode_theme(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let source = std::fs::read_to_string(path)?;
+        Self::from_vscode_json(&source).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// The `theme` prop of `MonacoEditor`. Note that Monaco has one theme per page,
+/// so switching it on one editor switches it on all of them.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditorTheme {
+    BuiltIn(BaseTheme),
+    Custom(MonacoTheme),
+}
+
+impl Default for EditorTheme {
+    fn default() -> Self {
+        EditorTheme::BuiltIn(BaseTheme::VsDark)
+    }
+}
+
+impl From<BaseTheme> for EditorTheme {
+    fn from(base: BaseTheme) -> Self {
+        EditorTheme::BuiltIn(base)
+    }
+}
+
+impl From<MonacoTheme> for EditorTheme {
+    fn from(theme: MonacoTheme) -> Self {
+        EditorTheme::Custom(theme)
+    }
+}
+
+impl EditorTheme {
+    pub fn name(&self) -> &str {
+        match self {
+            EditorTheme::BuiltIn(base) => base.name(),
+            EditorTheme::Custom(theme) => &theme.name,
+        }
+    }
+
+    /// The built-in theme this one is, or is based on.
+    pub fn base(&self) -> BaseTheme {
+        match self {
+            EditorTheme::BuiltIn(base) => *base,
+            EditorTheme::Custom(theme) => theme.base,
+        }
+    }
+
+    /// Calls that define the theme if needed and switch to it.
+    pub(crate) fn apply_ops(&self) -> Vec<Op> {
+        let mut ops = Vec::new();
+        if let EditorTheme::Custom(theme) = self {
+            ops.push(Op::call(Target::MonacoEditor, "defineTheme", [json!(theme.name), json!(theme)]));
+        }
+        ops.push(Op::call(Target::MonacoEditor, "setTheme", [json!(self.name())]));
+        ops
+    }
+}
+
+/// Lowercases and replaces everything but letters, digits and dashes.
+fn theme_id(name: &str) -> String {
+    let id: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    if id.is_empty() {
+        "custom-theme".to_string()
+    } else {
+        id
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeTheme {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    colors: BTreeMap<String, Value>,
+    #[serde(default)]
+    token_colors: Vec<VsCodeTokenColor>,
+}
+
+#[derive(Deserialize)]
+struct VsCodeTokenColor {
+    #[serde(default)]
+    scope: Option<Value>,
+    #[serde(default)]
+    settings: VsCodeTokenSettings,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeTokenSettings {
+    foreground: Option<String>,
+    background: Option<String>,
+    font_style: Option<String>,
+}
+
+impl VsCodeTheme {
+    fn into_monaco(self) -> MonacoTheme {
+        let base = match self.kind.as_deref() {
+            Some("light") => BaseTheme::Vs,
+            Some("hc") | Some("hcDark") => BaseTheme::HcBlack,
+            Some("hcLight") => BaseTheme::HcLight,
+            _ => BaseTheme::VsDark,
+        };
+        let mut theme = MonacoTheme::new(self.name.as_deref().unwrap_or("vscode-theme"), base);
+
+        theme.colors = self
+            .colors
+            .into_iter()
+            .filter_map(|(id, color)| color.as_str().map(|color| (id, color.to_string())))
+            .collect();
+
+        for entry in self.token_colors {
+            let settings = entry.settings;
+            let scopes: Vec<String> = match entry.scope {
+                Some(Value::String(scopes)) => scopes.split(',').map(|s| s.trim().to_string()).collect(),
+                Some(Value::Array(scopes)) => {
+                    scopes.iter().filter_map(|s| s.as_str()).map(|s| s.trim().to_string()).collect()
+                }
+                // A rule without scope carries the defaults
+                _ => {
+                    if let Some(foreground) = &settings.foreground {
+                        theme.colors.entry("editor.foreground".into()).or_insert_with(|| foreground.clone());
+                    }
+                    if let Some(background) = &settings.background {
+                        theme.colors.entry("editor.background".into()).or_insert_with(|| background.clone());
+                    }
+                    vec![String::new()]
+                }
+            };
+
+            for token in scopes {
+                theme.rules.push(TokenRule {
+                    token,
+                    foreground: settings.foreground.as_deref().map(hex),
+                    background: settings.background.as_deref().map(hex),
+                    font_style: settings.font_style.clone(),
+                });
+            }
+        }
+
+        theme
+    }
+}
+
+fn hex(color: &str) -> String {
+    color.trim_start_matches('#').to_string()
+}
+
+/// Drops `//` and `/* */` comments and trailing commas, which VS Code allows in theme files.
+fn strip_jsonc(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+    // A comma and the whitespace after it, held back until the next token shows whether it trails
+    let mut held: Option<String> = None;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = '\0';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            (',', _) => {
+                out.extend(held.take());
+                held = Some(",".to_string());
+            }
+            (c, _) if c.is_whitespace() => match &mut held {
+                Some(held) => held.push(c),
+                None => out.push(c),
+            },
+            _ => {
+                if let Some(held) = held.take() {
+                    let trailing = matches!(c, '}' | ']');
+                    out.push_str(if trailing { &held[1..] } else { &held });
+                }
+                in_string = c == '"';
+                out.push(c);
+            }
+        }
+    }
+    out.extend(held);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THEME: &str = r##"{
+        // Comments and trailing commas are fine in VS Code themes
+        "name": "Snippet Night",
+        "type": "dark",
+        "colors": {
+            "editor.background": "#101018", /* inline */
+            "editor.lineHighlightBackground": "#1c1c28",
+        },
+        "tokenColors": [
+            { "settings": { "foreground": "#d0d0e0" } },
+            { "scope": "comment", "settings": { "foreground": "#6a6a8a", "fontStyle": "italic" } },
+            { "scope": ["keyword", "storage.type"], "settings": { "foreground": "#c792ea" } },
+            { "scope": "string, string.quoted", "settings": { "foreground": "#c3e88d" } },
+        ],
+    }"##;
+
+    #[test]
+    fn converts_vscode_theme() {
+        let theme = MonacoTheme::from_vscode_json(THEME).unwrap();
+        assert_eq!(theme.name, "snippet-night");
+        assert_eq!(theme.base, BaseTheme::VsDark);
+        assert_eq!(theme.colors["editor.background"], "#101018");
+        assert_eq!(theme.colors["editor.foreground"], "#d0d0e0");
+
+        let tokens: Vec<&str> = theme.rules.iter().map(|rule| rule.token.as_str()).collect();
+        assert_eq!(tokens, ["", "comment", "keyword", "storage.type", "string", "string.quoted"]);
+        assert_eq!(theme.rules[1].foreground.as_deref(), Some("6a6a8a"));
+        assert_eq!(theme.rules[1].font_style.as_deref(), Some("italic"));
+    }
+
+    #[test]
+    fn jsonc_keeps_comment_markers_inside_strings() {
+        let stripped = strip_jsonc(r#"{ "url": "http://x/*y*/", "a": [1, 2,], }"#);
+        let value: Value = serde_json::from_str(&stripped).unwrap();
+        assert_eq!(value, json!({ "url": "http://x/*y*/", "a": [1, 2] }));
+    }
+
+    #[test]
+    fn large_themes_with_trailing_commas_parse() {
+        let rules: Vec<String> = (0..500)
+            .map(|i| format!(r##"{{ "scope": "s{i}", "settings": {{ "foreground": "#{i:06x}", }}, }}, // rule {i}"##))
+            .collect();
+        let source = format!(
+            "{{ \"type\": \"dark\", \"colors\": {{}}, \"tokenColors\": [\n{}\n/* end */ ], }}",
+            rules.join("\n")
+        );
+        let theme = MonacoTheme::from_vscode_json(&source).unwrap();
+        assert_eq!(theme.rules.len(), 500);
+        assert_eq!(theme.rules[499].foreground.as_deref(), Some("0001f3"));
+
+        let bundled = include_str!("../../assets/themes/snippet-night.json");
+        assert!(MonacoTheme::from_vscode_json(bundled).is_ok());
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// A cursor position, 1-based like Monaco's `IPosition`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub line_number: u32,
+    pub column: u32,
+}
+
+/// A text range, 1-based and end-exclusive like Monaco's `IRange`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Range {
+    pub start_line_number: u32,
+    pub start_column: u32,
+    pub end_line_number: u32,
+    pub end_column: u32,
+}
+
+impl Range {
+    /// Lines `start_line_number` to `end_line_number`, whole. The end column is past
+    /// any line's end, which Monaco clamps to the last line's length.
+    pub fn lines(start_line_number: u32, end_line_number: u32) -> Self {
+        Self { start_line_number, start_column: 1, end_line_number, end_column: u32::MAX }
+    }
+}
+
+/// Replaces `range` with `text`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    /// The model to edit; `None` means the one the request was about.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    pub range: Range,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range, text: impl Into<String>) -> Self {
+        Self { uri: None, range, text: text.into() }
+    }
+}
+
+/// Edits applied together, possibly across several models.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceEdit {
+    pub edits: Vec<TextEdit>,
+}
+
+/// A range in a particular model.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+/// One of the models open on the page, as sent along with cross-model requests.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct OpenDocument {
+    pub uri: String,
+    pub language: String,
+    pub value: String,
+}
+
+/// A block of lines that differs between the two sides of a diff, like Monaco's
+/// `ILineChange`. An end line of 0 means the block is empty on that side, i.e. the
+/// lines were only added or only removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineChange {
+    pub original_start_line_number: u32,
+    pub original_end_line_number: u32,
+    pub modified_start_line_number: u32,
+    pub modified_end_line_number: u32,
+}
+
+/// Cursor, scroll position and folding of a model in an editor, as returned by
+/// Monaco's `saveViewState`. Only good for handing back to the same kind of editor.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ViewState(pub serde_json::Value);
@@ -0,0 +1,160 @@
+//! Cross-snippet navigation for the demo: snippets call each other's top-level
+//! functions by name, so renames and definitions look at every open snippet.
+
+use crate::monaco::{
+    DefinitionProvider, DefinitionRequest, Location, OpenDocument, Range, RenameProvider, RenameRequest, TextEdit,
+    WorkspaceEdit,
+};
+
+pub struct SnippetLinks;
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Byte offsets of the whole-word occurrences of `word` in `line`.
+fn word_offsets<'a>(line: &'a str, word: &'a str) -> impl Iterator<Item = usize> + 'a {
+    line.match_indices(word).map(|(offset, _)| offset).filter(move |&offset| {
+        let before = line[..offset].chars().next_back();
+        let after = line[offset + word.len()..].chars().next();
+        !(before.is_some_and(is_ident) || after.is_some_and(is_ident))
+    })
+}
+
+/// `word` at `offset` on line `index`, with columns in UTF-16 code units like Monaco's.
+fn word_range(line: &str, index: usize, offset: usize, word: &str) -> Range {
+    let line_number = index as u32 + 1;
+    let start_column = line[..offset].encode_utf16().count() as u32 + 1;
+    Range {
+        start_line_number: line_number,
+        start_column,
+        end_line_number: line_number,
+        end_column: start_column + word.encode_utf16().count() as u32,
+    }
+}
+
+/// Every whole-word occurrence of `word` in `document`.
+fn occurrences(document: &OpenDocument, word: &str) -> Vec<Range> {
+    document
+        .value
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| word_offsets(line, word).map(move |offset| word_range(line, index, offset, word)))
+        .collect()
+}
+
+/// Where `word` is declared at the top level of `document`, if it is.
+fn declaration(document: &OpenDocument, word: &str) -> Option<Range> {
+    document.value.lines().enumerate().find_map(|(index, line)| {
+        let offset = word_offsets(line, word).find(|&offset| {
+            matches!(&line[..offset], "function " | "async function " | "class " | "const " | "let " | "var ")
+        })?;
+        Some(word_range(line, index, offset, word))
+    })
+}
+
+/// The open documents `request` may touch: the ones in its own language.
+fn related<'a>(documents: &'a [OpenDocument], uri: &str) -> impl Iterator<Item = &'a OpenDocument> {
+    let language = documents.iter().find(|document| document.uri == uri).map(|document| document.language.clone());
+    documents.iter().filter(move |document| Some(&document.language) == language.as_ref())
+}
+
+impl DefinitionProvider for SnippetLinks {
+    fn provide_definition(&self, _source: &str, request: &DefinitionRequest) -> Vec<Location> {
+        let Some(word) = request.word.as_deref() else { return Vec::new() };
+        related(&request.documents, &request.uri)
+            .filter_map(|document| {
+                declaration(document, word).map(|range| Location { uri: document.uri.clone(), range })
+            })
+            .collect()
+    }
+}
+
+impl RenameProvider for SnippetLinks {
+    fn provide_rename_edits(&self, _source: &str, request: &RenameRequest) -> Result<WorkspaceEdit, String> {
+        let word = request.word.as_deref().ok_or("Nothing to rename here")?;
+        if request.new_name.is_empty()
+            || request.new_name.starts_with(|c: char| c.is_ascii_digit())
+            || !request.new_name.chars().all(is_ident)
+        {
+            return Err(format!("{} is not a valid name", request.new_name));
+        }
+
+        let documents: Vec<&OpenDocument> = related(&request.documents, &request.uri).collect();
+        if !documents.iter().any(|document| declaration(document, word).is_some()) {
+            return Err(format!("{word} is not declared in any open snippet"));
+        }
+
+        let edits = documents
+            .iter()
+            .flat_map(|document| {
+                occurrences(document, word).into_iter().map(|range| TextEdit {
+                    uri: Some(document.uri.clone()),
+                    range,
+                    text: request.new_name.clone(),
+                })
+            })
+            .collect();
+        Ok(WorkspaceEdit { edits })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monaco::Position;
+
+    fn document(uri: &str, value: &str) -> OpenDocument {
+        OpenDocument { uri: uri.to_string(), language: "javascript".to_string(), value: value.to_string() }
+    }
+
+    fn range(line_number: u32, start_column: u32, end_column: u32) -> Range {
+        Range { start_line_number: line_number, start_column, end_line_number: line_number, end_column }
+    }
+
+    #[test]
+    fn renames_across_open_snippets() {
+        let documents = vec![
+            document("file:///a.js", "function total(xs) {}\nlet subtotal = total([]);"),
+            document("file:///b.js", "log(total([1]));"),
+        ];
+        let request = RenameRequest {
+            uri: "file:///b.js".to_string(),
+            position: Position { line_number: 1, column: 6 },
+            word: Some("total".to_string()),
+            new_name: "sum".to_string(),
+            documents,
+        };
+        let edits = SnippetLinks.provide_rename_edits("", &request).unwrap().edits;
+        let targets: Vec<(&str, Range)> =
+            edits.iter().map(|edit| (edit.uri.as_deref().unwrap(), edit.range)).collect();
+        assert_eq!(
+            targets,
+            vec![
+                ("file:///a.js", range(1, 10, 15)),
+                ("file:///a.js", range(2, 16, 21)),
+                ("file:///b.js", range(1, 5, 10)),
+            ]
+        );
+        assert!(edits.iter().all(|edit| edit.text == "sum"));
+    }
+
+    #[test]
+    fn undeclared_words_have_no_definition() {
+        let request = DefinitionRequest {
+            uri: "file:///a.js".to_string(),
+            position: Position { line_number: 1, column: 1 },
+            word: Some("missing".to_string()),
+            documents: vec![document("file:///a.js", "missing();")],
+        };
+        assert_eq!(SnippetLinks.provide_definition("", &request), Vec::new());
+    }
+
+    #[test]
+    fn columns_count_utf16_units() {
+        // Each emoji takes two UTF-16 code units, so two columns
+        let snippet = document("file:///a.js", "const 🎉done = 1;\nlog(\"🎉\", total);\nfunction total() {}");
+        assert_eq!(occurrences(&snippet, "total"), vec![range(2, 11, 16), range(3, 10, 15)]);
+        assert_eq!(declaration(&snippet, "total"), Some(range(3, 10, 15)));
+    }
+}
@@ -0,0 +1,154 @@
+//! The outline of a demo snippet: top-level functions, classes and bindings, plus
+//! class methods. Blocks are found by counting braces, which is plenty for the
+//! snippets this demo deals with.
+
+use crate::monaco::{DocumentSymbol, DocumentSymbolProvider, Range, SymbolKind};
+
+pub struct SnippetOutline;
+
+impl DocumentSymbolProvider for SnippetOutline {
+    fn provide_document_symbols(&self, source: &str) -> Vec<DocumentSymbol> {
+        let mut symbols: Vec<DocumentSymbol> = Vec::new();
+        // Symbols whose body is still open, outermost first, with the depth they were declared at
+        let mut open: Vec<(DocumentSymbol, i32)> = Vec::new();
+        let mut depth = 0;
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index as u32 + 1;
+            let in_class = open.last().is_some_and(|(symbol, _)| symbol.kind == SymbolKind::Class);
+            if depth == 0 || (in_class && depth == open.last().map_or(0, |(_, at)| at + 1)) {
+                if let Some((offset, name, kind)) = declaration(line, in_class) {
+                    // Monaco counts columns in UTF-16 code units
+                    let column = line[..offset].encode_utf16().count() as u32 + 1;
+                    let selection = Range {
+                        start_line_number: line_number,
+                        start_column: column,
+                        end_line_number: line_number,
+                        end_column: column + name.encode_utf16().count() as u32,
+                    };
+                    let range = Range { start_column: 1, ..selection };
+                    let symbol = DocumentSymbol::new(name, kind, range, selection).with_detail(line.trim());
+                    open.push((symbol, depth));
+                }
+            }
+
+            depth = (depth + line.matches('{').count() as i32 - line.matches('}').count() as i32).max(0);
+
+            // Close every symbol whose block ended on this line (or that never opened one)
+            while open.last().is_some_and(|(_, at)| depth <= *at) {
+                let Some((mut symbol, _)) = open.pop() else { break };
+                symbol.range.end_line_number = line_number;
+                symbol.range.end_column = line.encode_utf16().count() as u32 + 1;
+                match open.last_mut() {
+                    Some((parent, _)) => parent.children.push(symbol),
+                    None => symbols.push(symbol),
+                }
+            }
+        }
+
+        // Unterminated blocks still belong in the outline
+        while let Some((symbol, _)) = open.pop() {
+            match open.last_mut() {
+                Some((parent, _)) => parent.children.push(symbol),
+                None => symbols.push(symbol),
+            }
+        }
+        symbols
+    }
+}
+
+/// The symbol `line` declares, as the byte offset of its name, the name and its kind.
+fn declaration(line: &str, in_class: bool) -> Option<(usize, &str, SymbolKind)> {
+    // Every slice looked at is a suffix of `line`, so its length gives the offset
+    let ident = |text: &str| -> (usize, usize) {
+        let len = text.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$')).unwrap_or(text.len());
+        (line.len() - text.len(), len)
+    };
+    let trimmed = line.trim_start();
+    let unasync = trimmed.strip_prefix("async ").unwrap_or(trimmed);
+
+    let ((offset, len), kind) = if in_class {
+        let (offset, len) = ident(unasync);
+        let name = &line[offset..offset + len];
+        let is_method = unasync[len..].trim_start().starts_with('(');
+        if !is_method || matches!(name, "if" | "for" | "while" | "switch" | "catch" | "return") {
+            return None;
+        }
+        let kind = if name == "constructor" { SymbolKind::Constructor } else { SymbolKind::Method };
+        ((offset, len), kind)
+    } else if let Some(rest) = unasync.strip_prefix("function ") {
+        (ident(rest.trim_start_matches('*').trim_start()), SymbolKind::Function)
+    } else if let Some(rest) = trimmed.strip_prefix("class ") {
+        (ident(rest.trim_start()), SymbolKind::Class)
+    } else {
+        let (rest, kind) = match trimmed.split_once(' ') {
+            Some(("const", rest)) => (rest.trim_start(), SymbolKind::Constant),
+            Some(("let" | "var", rest)) => (rest.trim_start(), SymbolKind::Variable),
+            _ => return None,
+        };
+        let (offset, len) = ident(rest);
+        let value = rest[len..].trim_start().strip_prefix('=').unwrap_or("").trim_start();
+        let is_function = value.starts_with("function") || value.starts_with("async") || value.contains("=>");
+        ((offset, len), if is_function { SymbolKind::Function } else { kind })
+    };
+    (len > 0).then(|| (offset, &line[offset..offset + len], kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each symbol as `name Kind first-last [children]`, for comparing outlines at a glance.
+    fn shape(symbols: &[DocumentSymbol]) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|symbol| {
+                let children: Vec<&str> = symbol.children.iter().map(|child| child.name.as_str()).collect();
+                let range = symbol.range;
+                let lines = format!("{}-{}", range.start_line_number, range.end_line_number);
+                format!("{} {:?} {lines} [{}]", symbol.name, symbol.kind, children.join(", "))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn names_are_selected_after_the_keyword() {
+        // `😀` is not an identifier character, so the last two lines declare nothing
+        let symbols = SnippetOutline.provide_document_symbols("function f() {}\nconst 😀 = 1;\nlet 😀x = 3;");
+        let selection = symbols[0].selection_range;
+        assert_eq!(symbols.len(), 1);
+        assert_eq!((selection.start_column, selection.end_column), (10, 11));
+
+        // Columns are in UTF-16 code units
+        let symbols = SnippetOutline.provide_document_symbols("const 𝒳 = 1;\nfunction run() { return '😀'; }");
+        let selection = symbols[0].selection_range;
+        assert_eq!((symbols[0].name.as_str(), selection.start_column, selection.end_column), ("𝒳", 7, 9));
+        assert_eq!(symbols[1].range.end_column, 32);
+    }
+
+    #[test]
+    fn functions_inside_functions_are_not_listed() {
+        let source = "function outer() {\n  function inner() {\n    const x = 1;\n  }\n}\nconst after = () => {};";
+        let symbols = SnippetOutline.provide_document_symbols(source);
+        assert_eq!(shape(&symbols), ["outer Function 1-5 []", "after Function 6-6 []"]);
+    }
+
+    #[test]
+    fn classes_hold_their_methods() {
+        let source =
+            ["class Cart {", "  constructor() {", "    if (x) {", "    }", "  }", "  async total(items) {", "  }", "}"];
+        let symbols = SnippetOutline.provide_document_symbols(&source.join("\n"));
+        assert_eq!(shape(&symbols), ["Cart Class 1-8 [constructor, total]"]);
+        assert_eq!(shape(&symbols[0].children), ["constructor Constructor 2-5 []", "total Method 6-7 []"]);
+    }
+
+    #[test]
+    fn unclosed_blocks_still_show_up() {
+        let source = "class Open {\n  run() {\n    let x = 1;\nfunction ignored() {}";
+        let symbols = SnippetOutline.provide_document_symbols(source);
+        assert_eq!(shape(&symbols), ["Open Class 1-1 [run]"]);
+        // Nor does a stray closing brace hide what follows
+        let symbols = SnippetOutline.provide_document_symbols("}\nfunction next() {}");
+        assert_eq!(shape(&symbols), ["next Function 2-2 []"]);
+    }
+}